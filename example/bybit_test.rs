@@ -8,8 +8,13 @@ use bybit_rs::bybit::{
     asset::{self, Asset},
     http_manager::{HttpManager, Manager},
     market::{self, Market},
-    trade::{self, BatchOrderRequest, Trade},
+    trade::{self, Trade},
 };
+use bybit_rs::models::{
+    enums::{Category, Side, TimeInForce},
+    order::{BatchOrderRequest, PlaceOrderRequest},
+};
+use rust_decimal::Decimal;
 
 use bybit_rs::errors::app_error::AppError;
 use hmac_sha256::Hash;
@@ -74,21 +79,16 @@ async fn start_app() -> Result<(), AppError> {
 
     println!("============== PLACE A BATCH OF ACTIVE ORDERS  =============== ");
 
-    let mut order_1 = HashMap::new();
-    order_1.insert("category".to_owned(), "linear".to_owned());
-    order_1.insert("symbol".to_owned(), "BTCUSDT".to_owned());
-    order_1.insert("orderType".to_owned(), "Limit".to_owned());
-    order_1.insert("qty".to_owned(), "0.001".to_owned());
-    order_1.insert("price".to_owned(), "25001".to_owned());
-    order_1.insert("side".to_owned(), "Buy".to_owned());
-    order_1.insert("timeInForce".to_owned(), "GTC".to_owned());
-
-    let mut order_2 = order_1.clone();
-    order_2.insert("price".to_owned(), "25002".to_owned());
-    let mut request_params = BatchOrderRequest {
-        category: "linear".to_owned(),
-        request: vec![order_1, order_2],
-    };
+    let order_1 = PlaceOrderRequest::limit(
+        Category::Linear,
+        "BTCUSDT",
+        Side::Buy,
+        Decimal::new(1, 3),
+        Decimal::new(25001, 0),
+    )
+    .time_in_force(TimeInForce::GoodTillCancel);
+    let order_2 = order_1.clone().price(Decimal::new(25002, 0));
+    let request_params = BatchOrderRequest::new(vec![order_1, order_2])?;
 
     let trade: trade::TradeHTTP = trade::TradeHTTP::new(manager.clone());
 
@@ -1,4 +1,5 @@
 #![allow(unused)]
+#![allow(clippy::empty_line_after_outer_attr, clippy::empty_docs)]
 use async_trait::async_trait;
 use std::{
     collections::{BTreeMap, HashMap},
@@ -6,15 +7,24 @@ use std::{
     sync::Arc,
 };
 
-use futures::Future;
+use chrono::{DateTime, Utc};
+use futures::{stream::BoxStream, Future};
 use reqwest::Method;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
-use crate::endpoints::v5account;
+use crate::{
+    endpoints::v5account,
+    models::{
+        account::{TransactionLogEntry, WalletBalance},
+        Page,
+    },
+};
 
 use super::{
+    http_manager::{HttpManager, Manager},
+    pagination::{paginate, paginate_range, SEVEN_DAYS, THIRTY_DAYS},
     Result,
-    http_manager::{HttpManager, Manager}
 };
 
 #[async_trait]
@@ -77,6 +87,87 @@ pub trait Account {
         &self,
         query: HashMap<String, String>,
     ) -> Result<Value>;
+
+    async fn get_wallet_balance_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Vec<WalletBalance>> {
+        let page: Page<WalletBalance> = serde_json::from_value(self.get_wallet_balance(query).await?)?;
+        Ok(page.list)
+    }
+
+    async fn get_transaction_log_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Page<TransactionLogEntry>> {
+        Ok(serde_json::from_value(self.get_transaction_log(query).await?)?)
+    }
+
+    ///
+    /// Every borrow record from `get_borrow_history`, across all of its pages.
+    ///
+    fn get_borrow_history_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_borrow_history(query))
+    }
+
+    ///
+    /// Every transaction log entry from `get_transaction_log`, across all of its pages.
+    ///
+    fn get_transaction_log_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_transaction_log(query))
+    }
+
+    ///
+    /// Borrow records from `get_borrow_history` between `start` and `end`, requested in
+    /// thirty day windows.
+    ///
+    fn get_borrow_history_range<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate_range(query, start, end, THIRTY_DAYS, None, move |query| {
+            self.get_borrow_history(query)
+        })
+    }
+
+    ///
+    /// Transaction log entries from `get_transaction_log` between `start` and `end`,
+    /// requested in seven day windows.
+    ///
+    fn get_transaction_log_range<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate_range(query, start, end, SEVEN_DAYS, Some("id"), move |query| {
+            self.get_transaction_log(query)
+        })
+    }
 }
 
 pub struct AccountHTTP {
@@ -1,4 +1,5 @@
 #![allow(unused)]
+#![allow(clippy::empty_line_after_outer_attr, clippy::empty_docs)]
 use async_trait::async_trait;
 use std::{
     collections::{BTreeMap, HashMap},
@@ -6,17 +7,31 @@ use std::{
     sync::Arc,
 };
 
-use futures::Future;
+use chrono::{DateTime, Utc};
+use futures::{stream::BoxStream, Future};
 use reqwest::Method;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
-use crate::endpoints::v5asset;
+use crate::{
+    endpoints::v5asset,
+    errors::app_error::AppError,
+    models::asset::{
+        Conversion, ConvertCoins, ConvertConfirmation, ConvertHistory, ConvertQuote,
+        ConvertQuoteRequest, ConvertRecord, ConvertStatusResult,
+    },
+};
 
 use super::{
+    http_manager::{HttpManager, Manager},
+    pagination::{paginate, paginate_range, SEVEN_DAYS, THIRTY_DAYS},
     Result,
-    http_manager::{HttpManager, Manager}
 };
 
+/// Time a convert quote must have left, by the server clock, for `convert` to confirm it.
+/// Covers the round trip of the confirmation.
+const QUOTE_MARGIN_MS: i64 = 1000;
+
 
 #[async_trait]
 pub trait Asset {
@@ -144,6 +159,336 @@ pub trait Asset {
         &self,
         query: HashMap<String, String>,
     ) -> Result<Value>;
+
+    async fn get_convert_coin_list(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    async fn request_convert_quote(
+        &self,
+        request: &ConvertQuoteRequest,
+    ) -> Result<Value>;
+
+    async fn confirm_convert_quote(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    async fn get_convert_status(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    async fn get_convert_history(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    async fn get_convert_coin_list_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<ConvertCoins> {
+        Ok(serde_json::from_value(self.get_convert_coin_list(query).await?)?)
+    }
+
+    async fn request_convert_quote_typed(
+        &self,
+        request: &ConvertQuoteRequest,
+    ) -> Result<ConvertQuote> {
+        Ok(serde_json::from_value(self.request_convert_quote(request).await?)?)
+    }
+
+    async fn confirm_convert_quote_typed(
+        &self,
+        quote_tx_id: &str,
+    ) -> Result<ConvertConfirmation> {
+        let mut query = HashMap::new();
+        query.insert("quoteTxId".to_owned(), quote_tx_id.to_owned());
+        Ok(serde_json::from_value(self.confirm_convert_quote(query).await?)?)
+    }
+
+    async fn get_convert_status_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<ConvertRecord> {
+        let status: ConvertStatusResult =
+            serde_json::from_value(self.get_convert_status(query).await?)?;
+        Ok(status.result)
+    }
+
+    async fn get_convert_history_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<ConvertHistory> {
+        Ok(serde_json::from_value(self.get_convert_history(query).await?)?)
+    }
+
+    ///
+    /// Requests a quote and confirms it, unless by the server clock it expires within
+    /// `QUOTE_MARGIN_MS`. The convert is settled asynchronously; poll `get_convert_status`
+    /// for the outcome. The confirmation is sent once and never retried.
+    ///
+    async fn convert(&self, request: &ConvertQuoteRequest) -> Result<Conversion>;
+
+    ///
+    /// Every coin exchange record from `get_coin_exchange_records`, across all of its pages.
+    ///
+    fn get_coin_exchange_records_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_coin_exchange_records(query))
+    }
+
+    ///
+    /// Every option delivery record from `get_option_delivery_record`, across all of its pages.
+    ///
+    fn get_option_delivery_record_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_option_delivery_record(query))
+    }
+
+    ///
+    /// Every USDC settlement record from `get_usdc_contract_settlement`, across all of its pages.
+    ///
+    fn get_usdc_contract_settlement_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_usdc_contract_settlement(query))
+    }
+
+    ///
+    /// Every internal transfer from `get_internal_transfer_records`, across all of its pages.
+    ///
+    fn get_internal_transfer_records_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| {
+            self.get_internal_transfer_records(query)
+        })
+    }
+
+    ///
+    /// Every universal transfer from `get_universal_transfer_records`, across all of its pages.
+    ///
+    fn get_universal_transfer_records_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| {
+            self.get_universal_transfer_records(query)
+        })
+    }
+
+    ///
+    /// Every deposit from `get_deposit_records`, across all of its pages.
+    ///
+    fn get_deposit_records_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_deposit_records(query))
+    }
+
+    ///
+    /// Every sub account deposit from `get_sub_deposit_records`, across all of its pages.
+    ///
+    fn get_sub_deposit_records_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_sub_deposit_records(query))
+    }
+
+    ///
+    /// Every internal deposit from `get_internal_deposit_records`, across all of its pages.
+    ///
+    fn get_internal_deposit_records_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_internal_deposit_records(query))
+    }
+
+    ///
+    /// Every withdrawal from `get_withdrawal_records`, across all of its pages.
+    ///
+    fn get_withdrawal_records_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_withdrawal_records(query))
+    }
+
+    ///
+    /// Internal transfers from `get_internal_transfer_records` between `start` and `end`,
+    /// requested in seven day windows.
+    ///
+    fn get_internal_transfer_records_range<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate_range(
+            query,
+            start,
+            end,
+            SEVEN_DAYS,
+            Some("transferId"),
+            move |query| self.get_internal_transfer_records(query),
+        )
+    }
+
+    ///
+    /// Universal transfers from `get_universal_transfer_records` between `start` and `end`,
+    /// requested in seven day windows.
+    ///
+    fn get_universal_transfer_records_range<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate_range(
+            query,
+            start,
+            end,
+            SEVEN_DAYS,
+            Some("transferId"),
+            move |query| self.get_universal_transfer_records(query),
+        )
+    }
+
+    ///
+    /// Deposits from `get_deposit_records` between `start` and `end`, requested in thirty
+    /// day windows.
+    ///
+    fn get_deposit_records_range<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate_range(query, start, end, THIRTY_DAYS, None, move |query| {
+            self.get_deposit_records(query)
+        })
+    }
+
+    ///
+    /// Sub account deposits from `get_sub_deposit_records` between `start` and `end`,
+    /// requested in thirty day windows.
+    ///
+    fn get_sub_deposit_records_range<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate_range(query, start, end, THIRTY_DAYS, None, move |query| {
+            self.get_sub_deposit_records(query)
+        })
+    }
+
+    ///
+    /// Internal deposits from `get_internal_deposit_records` between `start` and `end`,
+    /// requested in thirty day windows.
+    ///
+    fn get_internal_deposit_records_range<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate_range(query, start, end, THIRTY_DAYS, Some("id"), move |query| {
+            self.get_internal_deposit_records(query)
+        })
+    }
+
+    ///
+    /// Withdrawals from `get_withdrawal_records` between `start` and `end`, requested in
+    /// thirty day windows.
+    ///
+    fn get_withdrawal_records_range<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate_range(
+            query,
+            start,
+            end,
+            THIRTY_DAYS,
+            Some("withdrawId"),
+            move |query| self.get_withdrawal_records(query),
+        )
+    }
 }
 
 pub struct AssetHTTP {
@@ -751,4 +1096,138 @@ impl Asset for AssetHTTP {
             )
             .await
     }
+
+    /// Query the coins that can be converted, with their limits.
+    ///
+    /// Required args:
+    ///     accountType (string): Wallet type, eb_convert_funding, eb_convert_uta, eb_convert_spot, eb_convert_contract or eb_convert_inverse
+    ///
+    /// Returns:
+    ///     Request results as dictionary.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/asset/convert/convert-coin-list
+    async fn get_convert_coin_list(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        self.http_manager
+            .submit_request(
+                Method::GET,
+                &v5asset::Asset::GetConvertCoinList.to_string(),
+                query,
+                true,
+            )
+            .await
+    }
+
+    /// Request a convert quote. It has to be confirmed with confirm_convert_quote before it expires.
+    ///
+    /// Required args:
+    ///     request (ConvertQuoteRequest): Account, coins and amount of the convert
+    ///
+    /// Returns:
+    ///     Request results as dictionary.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/asset/convert/apply-quote
+    async fn request_convert_quote(
+        &self,
+        request: &ConvertQuoteRequest,
+    ) -> Result<Value> {
+        self.http_manager
+            .submit_post_request(
+                Method::POST,
+                &v5asset::Asset::RequestConvertQuote.to_string(),
+                true,
+                request,
+            )
+            .await
+    }
+
+    /// Confirm a convert quote.
+    ///
+    /// Required args:
+    ///     quoteTxId (string): The quote tx ID from request_convert_quote
+    ///
+    /// Returns:
+    ///     Request results as dictionary.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/asset/convert/confirm-quote
+    async fn confirm_convert_quote(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        self.http_manager
+            .submit_post_request(
+                Method::POST,
+                &v5asset::Asset::ConfirmConvertQuote.to_string(),
+                true,
+                query,
+            )
+            .await
+    }
+
+    /// Query the status of a confirmed convert.
+    ///
+    /// Required args:
+    ///     quoteTxId (string): The quote tx ID
+    ///     accountType (string): Wallet type
+    ///
+    /// Returns:
+    ///     Request results as dictionary.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/asset/convert/get-convert-result
+    async fn get_convert_status(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        self.http_manager
+            .submit_request(
+                Method::GET,
+                &v5asset::Asset::GetConvertStatus.to_string(),
+                query,
+                true,
+            )
+            .await
+    }
+
+    /// Query the convert history. Paged with index and limit.
+    ///
+    /// Returns:
+    ///     Request results as dictionary.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/asset/convert/get-convert-history
+    async fn get_convert_history(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        self.http_manager
+            .submit_request(
+                Method::GET,
+                &v5asset::Asset::GetConvertHistory.to_string(),
+                query,
+                true,
+            )
+            .await
+    }
+
+    async fn convert(&self, request: &ConvertQuoteRequest) -> Result<Conversion> {
+        let quote = self.request_convert_quote_typed(request).await?;
+        let server_now = self.http_manager.time_sync().timestamp()? as i64;
+        if quote.expired_time.timestamp_millis() - QUOTE_MARGIN_MS <= server_now {
+            return Err(AppError::QuoteExpired {
+                quote_tx_id: quote.quote_tx_id,
+                expired_time: quote.expired_time,
+            });
+        }
+        let confirmation = self.confirm_convert_quote_typed(&quote.quote_tx_id).await?;
+        Ok(Conversion {
+            quote,
+            exchange_status: confirmation.exchange_status,
+        })
+    }
 }
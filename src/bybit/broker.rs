@@ -1,4 +1,5 @@
 #![allow(unused)]
+#![allow(clippy::empty_line_after_outer_attr, clippy::empty_docs)]
 use async_trait::async_trait;
 use std::{
     collections::{BTreeMap, HashMap},
@@ -8,17 +8,33 @@ use serde_json::{json, to_string, Value};
 use std::{
     collections::{BTreeMap, HashMap},
     sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use url::form_urlencoded::{self, Serializer};
 
 use sha2::Sha256;
 
-use crate::helpers::utils;
+use crate::{
+    endpoints::{v5asset, v5market, v5trade},
+    errors::app_error::AppError,
+    helpers::utils,
+};
+
+use super::{
+    rate_limit::RateLimiter,
+    response::ApiResponse,
+    signer::{HmacSigner, Signer},
+    time_sync::TimeSync,
+};
 
-type Error = Box<dyn std::error::Error + Send + Sync>;
+type Error = AppError;
 pub type HTTPManagerResult<T> = std::result::Result<T, Error>;
 
+/// Server timeout, timestamp outside recv_window, rate limits and internal errors.
+const RETRY_CODES: [i64; 5] = [10000, 10002, 10006, 10016, 10018];
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+const DEFAULT_RECV_WINDOW: u64 = 5000;
+
 #[async_trait]
 pub trait Manager {
     async fn auth(
@@ -26,14 +42,33 @@ pub trait Manager {
         req_params: &BTreeMap<String, String>,
         recv_window: u64,
         timestamp: u128,
-    ) -> Result<String, String>;
+    ) -> HTTPManagerResult<String>;
+    ///
+    /// Sends the request and returns the full reply envelope, whatever its `retCode`.
+    ///
+    async fn submit_request_raw(
+        &self,
+        method: Method,
+        path: &str,
+        query: HashMap<String, String>,
+        auth: bool,
+    ) -> HTTPManagerResult<ApiResponse>;
+
+    ///
+    /// Sends the request and returns its `result`. A non-zero `retCode` is returned as an error.
+    ///
     async fn submit_request(
         &self,
         method: Method,
         path: &str,
         query: HashMap<String, String>,
         auth: bool,
-    ) -> HTTPManagerResult<Value>;
+    ) -> HTTPManagerResult<Value> {
+        self.submit_request_raw(method, path, query, auth)
+            .await?
+            .into_result()
+    }
+
     async fn sign(
         &self,
         method: Method,
@@ -43,79 +78,393 @@ pub trait Manager {
         self.submit_request(method, path, query, true).await
     }
 
+    ///
+    /// Posts a signed JSON body and returns the full reply envelope, whatever its `retCode`.
+    ///
+    async fn submit_post_request_raw<T: Serialize + Send>(
+        &self,
+        path: &str,
+        json_input: T,
+    ) -> HTTPManagerResult<ApiResponse>;
+
+    ///
+    /// Posts a JSON body and returns its `result`. A non-zero `retCode` is returned as an error.
+    ///
     async fn submit_post_request<T: Serialize + Send>(
         &self,
         method: Method,
         path: &str,
         auth: bool,
         json_input: T,
-    ) -> HTTPManagerResult<Value>;
+    ) -> HTTPManagerResult<Value> {
+        self.submit_post_request_raw(path, json_input)
+            .await?
+            .into_result()
+    }
 }
 pub struct HttpManager {
     pub api_key: String,
-    api_secret: String,
+    signer: Arc<dyn Signer>,
     base_url: String,
     recv_window: u64,
-    ignore_codes: Vec<u64>,
+    ignore_codes: Vec<i64>,
     max_retries: u64,
+    retry_delay: Duration,
+    rate_limiter: RateLimiter,
+    time_sync: Arc<TimeSync>,
     client: reqwest::Client,
 }
 
 impl HttpManager {
-    ///
     ///
     /// Initializes a new HttpManager instance.
     ///
-    ///
     pub fn new(api_key: String, api_secret: String, testnet: bool) -> Self {
-        let sub_domain = if testnet { "api-testnet" } else { "api" };
-        let url = format!("https://{}.{}.com", sub_domain, "bybit");
-        let client = reqwest::Client::new();
+        let environment = if testnet {
+            Environment::Testnet
+        } else {
+            Environment::Mainnet
+        };
+        Self::from_parts(
+            api_key,
+            Arc::new(HmacSigner::new(&api_secret)),
+            environment.base_url().to_owned(),
+            DEFAULT_RECV_WINDOW,
+            reqwest::Client::new(),
+        )
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
 
+    pub fn recv_window(&self) -> u64 {
+        self.recv_window
+    }
+
+    ///
+    /// The signer for this key, to authenticate WebSocket streams with the same key.
+    ///
+    pub fn signer(&self) -> Arc<dyn Signer> {
+        self.signer.clone()
+    }
+
+    ///
+    /// Starts a builder for a custom host, timeouts, proxy or client.
+    ///
+    pub fn builder(api_key: String, api_secret: String) -> HttpManagerBuilder {
+        HttpManagerBuilder::new(api_key, api_secret)
+    }
+
+    fn from_parts(
+        api_key: String,
+        signer: Arc<dyn Signer>,
+        base_url: String,
+        recv_window: u64,
+        client: reqwest::Client,
+    ) -> Self {
+        let time_sync = Arc::new(TimeSync::new());
         HttpManager {
             api_key,
-            api_secret,
-            base_url: url,
-            recv_window: 5000,
+            signer,
+            base_url,
+            recv_window,
             ignore_codes: vec![],
             max_retries: 10,
+            retry_delay: Duration::from_millis(250),
+            rate_limiter: RateLimiter::default().with_time_sync(time_sync.clone()),
+            time_sync,
             client,
         }
     }
 
     ///
+    /// Number of times a failed request is retried. `0` disables retries.
+    /// Writes without a client id are never retried, see `is_retryable_write`.
     ///
-    /// Generates authentication signature
+    pub fn with_max_retries(mut self, max_retries: u64) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    ///
+    /// Delay before the first retry. It doubles on every attempt, up to 10 seconds.
+    ///
+    pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    ///
+    /// Replaces the default limiter, which queues calls at the documented limits.
+    /// The limiter reads reset timestamps with this manager's clock offset.
+    ///
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter.with_time_sync(self.time_sync.clone());
+        self
+    }
+
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    ///
+    /// Clock offset used to timestamp signed requests. Share it with the WebSocket
+    /// clients so their auth `expires` uses the same correction.
+    ///
+    pub fn time_sync(&self) -> Arc<TimeSync> {
+        self.time_sync.clone()
+    }
+
+    ///
+    /// Samples `/v5/market/time` and updates the clock offset. Returns the new offset
+    /// (server minus local) in milliseconds.
     ///
+    pub async fn sync_time(&self) -> HTTPManagerResult<i64> {
+        let path = v5market::MarketEnum::GetServerTime.to_string();
+        let sent = utils::generate_timestamp()?;
+        let response = self
+            .send_query(&Method::GET, &path, &HashMap::new(), false)
+            .await?;
+        let received = utils::generate_timestamp()?;
+
+        let time = response.time as u128;
+        let result = response.into_result()?;
+        let server = result["timeNano"]
+            .as_str()
+            .and_then(|nanos| nanos.parse::<u128>().ok())
+            .map(|nanos| nanos / 1_000_000)
+            .unwrap_or(time);
+        Ok(self.time_sync.record(sent, received, server))
+    }
+
+    ///
+    /// Re-syncs the clock every `interval` in the background, starting now.
+    /// The task ends once the last `Arc<HttpManager>` is dropped; failed samples are skipped.
+    ///
+    pub fn spawn_time_sync(
+        manager: &Arc<HttpManager>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let manager = Arc::downgrade(manager);
+        tokio::spawn(async move {
+            loop {
+                match manager.upgrade() {
+                    Some(manager) => {
+                        let _ = manager.sync_time().await;
+                    }
+                    None => return,
+                }
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+
+    ///
+    /// `retCode`s returned as success, with whatever `result` the reply carries.
+    ///
+    pub fn with_ignore_codes(mut self, ignore_codes: Vec<i64>) -> Self {
+        self.ignore_codes = ignore_codes;
+        self
+    }
+
+    ///
+    /// Generates authentication signature
     ///
     pub async fn generate_signature(
         &self,
         secret: &str,
         msg: &str,
-    ) -> Result<String, Box<dyn std::error::Error>> {
+    ) -> HTTPManagerResult<String> {
         let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
         let tag = hmac::sign(&key, msg.as_bytes());
         Ok(hex::encode(tag.as_ref()))
     }
 }
+///
+/// Bybit REST hosts.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Environment {
+    Mainnet,
+    Testnet,
+    /// Demo trading: mainnet market data with simulated balances.
+    Demo,
+    /// Mainnet through the alternative `bytick.com` domain.
+    Bytick,
+}
+
+impl Environment {
+    pub fn base_url(&self) -> &'static str {
+        match self {
+            Environment::Mainnet => "https://api.bybit.com",
+            Environment::Testnet => "https://api-testnet.bybit.com",
+            Environment::Demo => "https://api-demo.bybit.com",
+            Environment::Bytick => "https://api.bytick.com",
+        }
+    }
+}
+
+///
+/// Builds an `HttpManager` with a custom host and HTTP client settings.
+/// Retries, rate limiting and ignored codes are set on the built manager.
+///
+pub struct HttpManagerBuilder {
+    api_key: String,
+    signer: Arc<dyn Signer>,
+    base_url: String,
+    recv_window: u64,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<String>,
+    user_agent: Option<String>,
+    headers: header::HeaderMap,
+    client: Option<reqwest::Client>,
+}
+
+impl HttpManagerBuilder {
+    pub fn new(api_key: String, api_secret: String) -> Self {
+        HttpManagerBuilder {
+            api_key,
+            signer: Arc::new(HmacSigner::new(&api_secret)),
+            base_url: Environment::Mainnet.base_url().to_owned(),
+            recv_window: DEFAULT_RECV_WINDOW,
+            connect_timeout: None,
+            timeout: None,
+            proxy: None,
+            user_agent: None,
+            headers: header::HeaderMap::new(),
+            client: None,
+        }
+    }
+
+    ///
+    /// Signs with `signer` instead of HMAC with the secret, e.g. an `RsaSigner` for RSA keys.
+    ///
+    pub fn signer(mut self, signer: Arc<dyn Signer>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    pub fn environment(mut self, environment: Environment) -> Self {
+        self.base_url = environment.base_url().to_owned();
+        self
+    }
+
+    ///
+    /// Any host, e.g. a regional domain or a local mock server. A trailing `/` is dropped.
+    ///
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_owned();
+        self
+    }
+
+    pub fn recv_window(mut self, recv_window: u64) -> Self {
+        self.recv_window = recv_window;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    ///
+    /// Total time allowed for a request, from connecting to reading the body.
+    ///
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    ///
+    /// Routes every request through a proxy, e.g. `socks5://127.0.0.1:1080` or `http://proxy:3128`.
+    ///
+    pub fn proxy(mut self, proxy_url: &str) -> Self {
+        self.proxy = Some(proxy_url.to_owned());
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_owned());
+        self
+    }
+
+    ///
+    /// Adds a header sent with every request, e.g. `Referer` for broker ids.
+    ///
+    pub fn default_header(mut self, name: header::HeaderName, value: header::HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    ///
+    /// Uses a ready-made client. It cannot be combined with the timeout, proxy,
+    /// user agent or header settings, which only apply to a client built here.
+    ///
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn build(self) -> HTTPManagerResult<HttpManager> {
+        let client = match self.client {
+            Some(client) => {
+                if self.connect_timeout.is_some()
+                    || self.timeout.is_some()
+                    || self.proxy.is_some()
+                    || self.user_agent.is_some()
+                    || !self.headers.is_empty()
+                {
+                    return Err(AppError::InvalidInput(
+                        "Client settings cannot be applied to an injected reqwest::Client"
+                            .to_owned(),
+                    ));
+                }
+                client
+            }
+            None => {
+                let mut builder = reqwest::Client::builder().default_headers(self.headers);
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(proxy_url) = &self.proxy {
+                    builder = builder.proxy(reqwest::Proxy::all(proxy_url)?);
+                }
+                if let Some(user_agent) = &self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                builder.build()?
+            }
+        };
+        Ok(HttpManager::from_parts(
+            self.api_key,
+            self.signer,
+            self.base_url,
+            self.recv_window,
+            client,
+        ))
+    }
+}
+
 #[async_trait]
 impl Manager for HttpManager {
-    ///
     ///
     /// Generates authentication signature per Bybit API specifications
     ///
-    ///
     async fn auth(
         &self,
         req_params: &BTreeMap<String, String>,
         recv_window: u64,
         timestamp: u128,
-    ) -> Result<String, String> {
-        if self.api_key.is_empty() || self.api_secret.is_empty() {
-            return Err("Authenticated endpoints require keys.".to_string());
+    ) -> HTTPManagerResult<String> {
+        if self.api_key.is_empty() {
+            return Err(AppError::MissingCredentials);
         }
-        let param_string =
-            serde_urlencoded::to_string(req_params).map_err(|e| format!("Error: {:?}", e))?;
+        let param_string = serde_urlencoded::to_string(req_params)?;
 
         let val = format!(
             "{time}{api_key}{recv_window}{params}",
@@ -124,12 +473,10 @@ impl Manager for HttpManager {
             recv_window = recv_window,
             params = param_string,
         );
-        let sign_result = self.generate_signature(&self.api_secret, &val).await;
-        let sign = sign_result.map_err(|e| format!("Error: {:?}", e))?;
+        let sign = self.signer.sign(&val)?;
         Ok(format!("{}&sign={}", param_string, sign))
     }
 
-    ///
     ///
     /// Submits the request to the API.
     /// Notes
@@ -139,76 +486,169 @@ impl Manager for HttpManager {
     /// JSONified prior to submitting request.
     /// -------------------
     ///
-    async fn submit_request(
+    async fn submit_request_raw(
         &self,
         method: Method,
         path: &str,
         parameters: HashMap<String, String>,
         auth: bool,
+    ) -> HTTPManagerResult<ApiResponse> {
+        let retry = method == Method::GET || is_retryable_write(path, &json!(parameters));
+        self.with_retries(retry, || self.send_query(&method, path, &parameters, auth))
+            .await
+    }
+
+    async fn submit_post_request_raw<T: Serialize + Send>(
+        &self,
+        path: &str,
+        json_input: T,
+    ) -> HTTPManagerResult<ApiResponse> {
+        let json_value = serde_json::to_value(&json_input)?;
+        let json_string = json_value.to_string();
+        let retry = is_retryable_write(path, &json_value);
+        self.with_retries(retry, || self.send_json(path, &json_string))
+            .await
+    }
+
+    async fn submit_request(
+        &self,
+        method: Method,
+        path: &str,
+        query: HashMap<String, String>,
+        auth: bool,
     ) -> HTTPManagerResult<Value> {
-        let request_url = format!("{}{}", self.base_url, path);
+        let response = self.submit_request_raw(method, path, query, auth).await?;
+        self.unwrap_response(response)
+    }
+
+    async fn submit_post_request<T: Serialize + Send>(
+        &self,
+        method: Method,
+        path: &str,
+        auth: bool,
+        json_input: T,
+    ) -> HTTPManagerResult<Value> {
+        let response = self.submit_post_request_raw(path, json_input).await?;
+        self.unwrap_response(response)
+    }
+}
+
+impl HttpManager {
+    ///
+    /// Runs `send` until it succeeds, fails with an error that is not worth retrying,
+    /// or `max_retries` is used up. Transport errors, 5xx replies and the codes in
+    /// `RETRY_CODES` are retried with exponential backoff, for reads and for the writes
+    /// `is_retryable_write` accepts.
+    ///
+    async fn with_retries<F, Fut>(&self, retry: bool, mut send: F) -> HTTPManagerResult<ApiResponse>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = HTTPManagerResult<ApiResponse>>,
+    {
+        let mut attempt = 0;
+        loop {
+            let outcome = send().await;
+            let retryable = match &outcome {
+                Ok(response) => RETRY_CODES.contains(&response.ret_code),
+                Err(e) => is_transient(e),
+            };
+            if !retry || !retryable || attempt >= self.max_retries {
+                return outcome;
+            }
+            tokio::time::sleep(self.retry_backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    fn retry_backoff(&self, attempt: u64) -> Duration {
+        let base = self
+            .retry_delay
+            .saturating_mul(2u32.saturating_pow(attempt.min(16) as u32))
+            .min(MAX_RETRY_DELAY);
+        let half = base / 2;
+        half + half.mul_f64(rand::random::<f64>())
+    }
+
+    fn unwrap_response(&self, response: ApiResponse) -> HTTPManagerResult<Value> {
+        if self.ignore_codes.contains(&response.ret_code) {
+            return Ok(response.result);
+        }
+        response.into_result()
+    }
+
+    async fn send_query(
+        &self,
+        method: &Method,
+        path: &str,
+        parameters: &HashMap<String, String>,
+        auth: bool,
+    ) -> HTTPManagerResult<ApiResponse> {
+        self.rate_limiter.acquire(path, auth).await?;
+
+        // The signed string is sent as-is: as the query for GET and DELETE, as the body otherwise.
+        let (request_url, payload) = match *method {
+            Method::GET | Method::DELETE => {
+                let query = utils::canonical_query_string(parameters)?;
+                let url = if query.is_empty() {
+                    format!("{}{}", self.base_url, path)
+                } else {
+                    format!("{}{}?{}", self.base_url, path, query)
+                };
+                (url, query)
+            }
+            Method::POST | Method::PUT => {
+                let sorted: BTreeMap<_, _> = parameters.iter().collect();
+                (
+                    format!("{}{}", self.base_url, path),
+                    serde_json::to_string(&sorted)?,
+                )
+            }
+            _ => {
+                return Err(AppError::InvalidInput(format!(
+                    "Unsupported HTTP method {}",
+                    method
+                )));
+            }
+        };
 
         let mut request_builder = self.client.request(method.clone(), &request_url);
 
         if auth {
-            let timestamp = utils::generate_timestamp()? as u128;
-            let param_string = serde_urlencoded::to_string(&parameters)?;
+            let timestamp = self.time_sync.timestamp()?;
             let val = format!(
                 "{time}{api_key}{recv_window}{params}",
                 time = timestamp,
                 api_key = self.api_key,
                 recv_window = self.recv_window,
-                params = param_string,
+                params = payload,
             );
 
-            let signature = self
-                .generate_signature(&self.api_secret, &val)
-                .await
-                .map_err(|e| format!("Error: {:?}", e))?;
+            let signature = self.signer.sign(&val)?;
 
             let headers = utils::build_private_headers(
                 &self.api_key,
                 &signature,
                 timestamp,
                 &self.recv_window.to_string(),
+                self.signer.sign_type(),
             );
             request_builder = request_builder.headers(headers);
-        } // do we need to handle the else block if the one is not authenticated?
-
-        let response = match method {
-            Method::GET | Method::DELETE => request_builder
-                .query(&parameters)
-                .send()
-                .await
-                .map_err(|e| format!("Error: {:?}", e))?,
-            Method::POST | Method::PUT => {
-                request_builder = request_builder.header(header::CONTENT_TYPE, "application/json");
-                request_builder.json(&parameters).send().await?
-            }
-            _ => {
-                return Err(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::InvalidInput,
-                    "Unsupported HTTP method",
-                )));
-            }
-        };
+        }
 
-        let body_text = response.text().await?;
-        let body: Value = serde_json::from_str(&body_text)?;
+        if *method == Method::POST || *method == Method::PUT {
+            request_builder = request_builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(payload);
+        }
+        let response = request_builder.send().await?;
 
-        Ok(body)
+        self.rate_limiter.update(path, auth, response.headers());
+        parse_body(response).await
     }
 
-    async fn submit_post_request<T: Serialize + Send>(
-        &self,
-        method: Method,
-        path: &str,
-        auth: bool,
-        json_input: T,
-    ) -> HTTPManagerResult<Value> {
-        let timestamp = utils::generate_timestamp()? as u128;
-
-        let json_string = serde_json::to_string(&json_input)?; // Convert the HashMap into a JSON string.
+    async fn send_json(&self, path: &str, json_string: &str) -> HTTPManagerResult<ApiResponse> {
+        self.rate_limiter.acquire(path, true).await?;
+        let timestamp = self.time_sync.timestamp()?;
 
         let val = format!(
             "{time}{api_key}{recv_window}{params}",
@@ -218,28 +658,83 @@ impl Manager for HttpManager {
             params = json_string,
         );
 
-        let signature = self
-            .generate_signature(&self.api_secret, &val)
-            .await
-            .map_err(|e| format!("Error: {:?}", e))?;
+        let signature = self.signer.sign(&val)?;
 
         let request_url = format!("{}{}", self.base_url, path);
         let response = self
             .client
             .post(&request_url)
-            .json(&json_input) // Pass a reference to the HashMap.
+            .body(json_string.to_owned()) // Send exactly the string that was signed.
             .headers(utils::build_private_headers(
                 &self.api_key,
                 &signature,
                 timestamp,
                 &self.recv_window.to_string(),
+                self.signer.sign_type(),
             ))
             .send()
             .await?;
 
-        let body_text = response.text().await?;
-        let body: Value = serde_json::from_str(&body_text)?;
+        self.rate_limiter.update(path, true, response.headers());
+        parse_body(response).await
+    }
+}
+
+///
+/// Reads the reply envelope. A non-success status without an envelope
+/// (gateway errors, html pages) is reported as `AppError::HttpStatus`.
+///
+async fn parse_body(response: reqwest::Response) -> HTTPManagerResult<ApiResponse> {
+    let status = response.status();
+    let body_text = response.text().await?;
+    match serde_json::from_str(&body_text) {
+        Ok(body) => Ok(body),
+        Err(_) if !status.is_success() => Err(AppError::HttpStatus {
+            status: status.as_u16(),
+            body: body_text,
+        }),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn is_transient(error: &AppError) -> bool {
+    match error {
+        AppError::RequestError(e) => !e.is_builder(),
+        AppError::HttpStatus { status, .. } => *status >= 500,
+        _ => false,
+    }
+}
+
+///
+/// A write may already have run when its reply was lost, so it is only resent when it
+/// carries a client id that Bybit rejects as a duplicate: the `orderLinkId` of new orders,
+/// the `requestId` of a withdrawal or the `transferId` of a transfer.
+///
+fn is_retryable_write(path: &str, body: &Value) -> bool {
+    let has_id = |field: &str| body[field].as_str().map_or(false, |id| !id.is_empty());
+    if path == v5trade::Trade::PlaceOrder.to_string()
+        || path == v5trade::Trade::BatchPlaceOrder.to_string()
+    {
+        has_order_link_ids(body)
+    } else if path == v5asset::Asset::Withdraw.to_string() {
+        has_id("requestId")
+    } else if path == v5asset::Asset::CreateInternalTransfer.to_string()
+        || path == v5asset::Asset::CreateUniversalTransfer.to_string()
+    {
+        has_id("transferId")
+    } else {
+        false
+    }
+}
 
-        Ok(body)
+fn has_order_link_ids(body: &Value) -> bool {
+    let has_link_id = |order: &Value| {
+        order["orderLinkId"]
+            .as_str()
+            .map_or(false, |link_id| !link_id.is_empty())
+    };
+    match body["request"].as_array() {
+        Some(orders) => !orders.is_empty() && orders.iter().all(has_link_id),
+        None => has_link_id(body),
     }
 }
@@ -0,0 +1,152 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use rust_decimal::Decimal;
+
+use crate::models::{
+    enums::{Category, OrderType, Side},
+    market::InstrumentInfo,
+    order::PlaceOrderRequest,
+};
+
+use super::{
+    http_manager::HttpManager,
+    market::{Market, MarketHTTP},
+    AppError, Result,
+};
+
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Largest page `/v5/market/instruments-info` returns.
+const PAGE_LIMIT: &str = "1000";
+
+struct Snapshot {
+    loaded_at: Instant,
+    instruments: HashMap<String, Arc<InstrumentInfo>>,
+}
+
+///
+/// Cache of `/v5/market/instruments-info`, loaded one category at a time and reloaded
+/// once older than the refresh interval. Used to round prices and quantities to the
+/// instrument filters and to check orders before they are sent.
+///
+pub struct InstrumentRegistry {
+    market: MarketHTTP,
+    refresh_interval: Duration,
+    cache: Mutex<HashMap<Category, Snapshot>>,
+}
+
+impl InstrumentRegistry {
+    pub fn new(http_manager: Arc<HttpManager>) -> Self {
+        InstrumentRegistry {
+            market: MarketHTTP::new(http_manager),
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = refresh_interval;
+        self
+    }
+
+    ///
+    /// Loads every instrument of `category`, following `nextPageCursor`, and replaces
+    /// the cached ones. Returns the number of instruments.
+    ///
+    pub async fn refresh(&self, category: Category) -> Result<usize> {
+        let mut instruments = HashMap::new();
+        let mut cursor = String::new();
+        loop {
+            let mut query = HashMap::new();
+            query.insert("category".to_owned(), category.to_string());
+            query.insert("limit".to_owned(), PAGE_LIMIT.to_owned());
+            if !cursor.is_empty() {
+                query.insert("cursor".to_owned(), cursor.clone());
+            }
+            let page = self.market.get_instruments_info_typed(query).await?;
+            let done = page.list.is_empty() || page.next_page_cursor.is_empty();
+            for instrument in page.list {
+                instruments.insert(instrument.symbol.clone(), Arc::new(instrument));
+            }
+            if done {
+                break;
+            }
+            cursor = page.next_page_cursor;
+        }
+
+        let count = instruments.len();
+        self.cache.lock().unwrap().insert(
+            category,
+            Snapshot {
+                loaded_at: Instant::now(),
+                instruments,
+            },
+        );
+        Ok(count)
+    }
+
+    ///
+    /// The cached instrument, without loading or refreshing.
+    ///
+    pub fn cached(&self, category: Category, symbol: &str) -> Option<Arc<InstrumentInfo>> {
+        let cache = self.cache.lock().unwrap();
+        cache.get(&category)?.instruments.get(symbol).cloned()
+    }
+
+    ///
+    /// The instrument, loading the category first if it is missing or stale.
+    ///
+    pub async fn get(&self, category: Category, symbol: &str) -> Result<Arc<InstrumentInfo>> {
+        if self.is_stale(category) {
+            self.refresh(category).await?;
+        }
+        self.cached(category, symbol).ok_or_else(|| {
+            AppError::InvalidInput(format!("Unknown {} symbol {}", category, symbol))
+        })
+    }
+
+    pub async fn round_price(
+        &self,
+        category: Category,
+        symbol: &str,
+        price: Decimal,
+    ) -> Result<Decimal> {
+        Ok(self.get(category, symbol).await?.round_price(price))
+    }
+
+    pub async fn round_qty(
+        &self,
+        category: Category,
+        symbol: &str,
+        qty: Decimal,
+    ) -> Result<Decimal> {
+        Ok(self.get(category, symbol).await?.round_qty(qty))
+    }
+
+    ///
+    /// Checks `request` against the filters of its instrument: quantity step and limits,
+    /// tick size, price range and order value. Spot market buys are sized in the quote
+    /// coin, so only their order value is checked.
+    ///
+    pub async fn validate_order(&self, request: &PlaceOrderRequest) -> Result<()> {
+        let instrument = self.get(request.category, &request.symbol).await?;
+        if request.category == Category::Spot
+            && request.order_type == OrderType::Market
+            && request.side == Side::Buy
+        {
+            return instrument.check_order_value(request.qty);
+        }
+        instrument.check_order(request.order_type, request.qty, request.price)
+    }
+
+    fn is_stale(&self, category: Category) -> bool {
+        match self.cache.lock().unwrap().get(&category) {
+            Some(snapshot) => snapshot.loaded_at.elapsed() >= self.refresh_interval,
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,570 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::{self, File},
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+use chrono::{DateTime, TimeZone, Utc};
+use parquet::{
+    data_type::{ByteArray, ByteArrayType, Int64Type},
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::parser::parse_message_type,
+};
+use rust_decimal::Decimal;
+
+use crate::models::{
+    enums::{Category, Interval},
+    market::{Kline, PriceKline},
+};
+
+use super::{
+    http_manager::HttpManager,
+    market::{Market, MarketHTTP},
+    AppError, Result,
+};
+
+/// Most bars `/v5/market/kline` returns per request.
+const PAGE_LIMIT: usize = 1000;
+
+/// Rows per Parquet row group.
+const ROW_GROUP_SIZE: usize = 100_000;
+
+/// Monthly bars are treated as closed once they started this long ago.
+const MONTH_MILLIS: i64 = 31 * 24 * 60 * 60 * 1000;
+
+///
+/// Which kline endpoint to archive.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KlineKind {
+    /// `/v5/market/kline`, with volume and turnover.
+    Trade,
+    Mark,
+    Index,
+    /// Linear contracts only.
+    PremiumIndex,
+}
+
+impl KlineKind {
+    fn file_stem(&self) -> &'static str {
+        match self {
+            KlineKind::Trade => "kline",
+            KlineKind::Mark => "mark_price_kline",
+            KlineKind::Index => "index_price_kline",
+            KlineKind::PremiumIndex => "premium_index_price_kline",
+        }
+    }
+
+    fn has_volume(&self) -> bool {
+        *self == KlineKind::Trade
+    }
+}
+
+///
+/// One stored candle. `volume` and `turnover` are only set for `KlineKind::Trade`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bar {
+    pub start_time: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Option<Decimal>,
+    pub turnover: Option<Decimal>,
+}
+
+impl From<Kline> for Bar {
+    fn from(kline: Kline) -> Self {
+        Bar {
+            start_time: kline.start_time,
+            open: kline.open,
+            high: kline.high,
+            low: kline.low,
+            close: kline.close,
+            volume: Some(kline.volume),
+            turnover: Some(kline.turnover),
+        }
+    }
+}
+
+impl From<PriceKline> for Bar {
+    fn from(kline: PriceKline) -> Self {
+        Bar {
+            start_time: kline.start_time,
+            open: kline.open,
+            high: kline.high,
+            low: kline.low,
+            close: kline.close,
+            volume: None,
+            turnover: None,
+        }
+    }
+}
+
+///
+/// What to archive: one symbol, interval and kline kind over `start..=end`.
+///
+#[derive(Debug, Clone)]
+pub struct KlineRequest {
+    pub category: Category,
+    pub symbol: String,
+    pub interval: Interval,
+    pub kind: KlineKind,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl KlineRequest {
+    pub fn new(
+        category: Category,
+        symbol: &str,
+        interval: Interval,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Self {
+        KlineRequest {
+            category,
+            symbol: symbol.to_owned(),
+            interval,
+            kind: KlineKind::Trade,
+            start,
+            end,
+        }
+    }
+
+    pub fn kind(mut self, kind: KlineKind) -> Self {
+        self.kind = kind;
+        self
+    }
+}
+
+///
+/// Bars missing between two stored bars, which Bybit did not return either.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gap {
+    /// Start of the last bar before the gap.
+    pub after: DateTime<Utc>,
+    /// Start of the first bar after the gap.
+    pub before: DateTime<Utc>,
+    pub missing: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveReport {
+    /// Bars added by this run.
+    pub added: usize,
+    /// Bars stored for the symbol, interval and kind.
+    pub total: usize,
+    /// Gaps left inside the requested range. Always empty for monthly bars.
+    pub gaps: Vec<Gap>,
+    pub csv_path: PathBuf,
+    pub parquet_path: PathBuf,
+}
+
+///
+/// Downloads closed klines into `{dir}/{category}/{symbol}/{kind}_{interval}.csv` and a
+/// Parquet copy next to it. Each `sync` only requests what is not stored yet: the part of
+/// the range before the first stored bar, after the last one, and gaps in between.
+/// Ranges are walked backwards from their end, `PAGE_LIMIT` bars per request.
+///
+/// Ranges Bybit returned no bars for, such as gaps or the time before a listing, are
+/// recorded in a `.gaps` file next to the CSV and not requested again. Delete it to
+/// retry them.
+///
+/// Prices are stored as decimal strings in both files so no precision is lost.
+///
+pub struct KlineArchive {
+    market: MarketHTTP,
+    dir: PathBuf,
+}
+
+impl KlineArchive {
+    pub fn new<P: Into<PathBuf>>(http_manager: Arc<HttpManager>, dir: P) -> Self {
+        KlineArchive {
+            market: MarketHTTP::new(http_manager),
+            dir: dir.into(),
+        }
+    }
+
+    pub fn csv_path(&self, request: &KlineRequest) -> PathBuf {
+        self.base_path(request).with_extension("csv")
+    }
+
+    pub fn parquet_path(&self, request: &KlineRequest) -> PathBuf {
+        self.base_path(request).with_extension("parquet")
+    }
+
+    pub fn gaps_path(&self, request: &KlineRequest) -> PathBuf {
+        self.base_path(request).with_extension("gaps")
+    }
+
+    ///
+    /// Fetches the bars of `request` that are not stored yet and rewrites both files.
+    ///
+    pub async fn sync(&self, request: &KlineRequest) -> Result<ArchiveReport> {
+        let (start, end) = (
+            request.start.timestamp_millis(),
+            request.end.timestamp_millis(),
+        );
+        if start > end {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid kline range {}..{}",
+                request.start, request.end
+            )));
+        }
+        let step = request.interval.duration().map(|d| d.as_millis() as i64);
+        let csv_path = self.csv_path(request);
+        let gaps_path = self.gaps_path(request);
+        let mut bars = read_csv(&csv_path)?;
+        let mut empty = read_ranges(&gaps_path)?;
+        let (before, known) = (bars.len(), empty.len());
+
+        for (from, to) in missing_ranges(&bars, start, end, step, &empty)? {
+            self.fetch_range(request, from, to, step, &mut bars).await?;
+        }
+
+        // everything before the first bar and between bars has been requested by now
+        let gaps = find_gaps(&bars, start, end, step)?;
+        for gap in &gaps {
+            empty.insert((
+                gap.after.timestamp_millis() + 1,
+                gap.before.timestamp_millis() - 1,
+            ));
+        }
+        if let Some(first) = bars.keys().next() {
+            if start < *first {
+                empty.insert((start, first - 1));
+            }
+        }
+
+        let parquet_path = self.parquet_path(request);
+        let changed = bars.len() != before || !parquet_path.exists();
+        if changed || empty.len() != known {
+            if let Some(parent) = csv_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        if changed {
+            write_csv(&csv_path, &bars, request.kind)?;
+            write_parquet(&parquet_path, &bars, request.kind)?;
+        }
+        if empty.len() != known {
+            write_ranges(&gaps_path, &empty)?;
+        }
+
+        Ok(ArchiveReport {
+            added: bars.len() - before,
+            total: bars.len(),
+            gaps,
+            csv_path,
+            parquet_path,
+        })
+    }
+
+    ///
+    /// Stored bars in `start..=end`, oldest first.
+    ///
+    pub fn read(&self, request: &KlineRequest) -> Result<Vec<Bar>> {
+        let bars = read_csv(&self.csv_path(request))?;
+        let range = request.start.timestamp_millis()..=request.end.timestamp_millis();
+        Ok(bars.range(range).map(|(_, bar)| bar.clone()).collect())
+    }
+
+    fn base_path(&self, request: &KlineRequest) -> PathBuf {
+        self.dir
+            .join(request.category.as_str())
+            .join(&request.symbol)
+            .join(format!("{}_{}", request.kind.file_stem(), request.interval))
+    }
+
+    async fn fetch_range(
+        &self,
+        request: &KlineRequest,
+        from: i64,
+        to: i64,
+        step: Option<i64>,
+        bars: &mut BTreeMap<i64, Bar>,
+    ) -> Result<()> {
+        let now = Utc::now().timestamp_millis();
+        let mut end = to;
+        loop {
+            let page = self.fetch_page(request, from, end).await?;
+            let oldest = match page.iter().map(|bar| bar.start_time).min() {
+                Some(oldest) => oldest.timestamp_millis(),
+                None => return Ok(()),
+            };
+            for bar in page {
+                let start = bar.start_time.timestamp_millis();
+                let closes = start + step.unwrap_or(MONTH_MILLIS);
+                if (from..=to).contains(&start) && closes <= now {
+                    bars.insert(start, bar);
+                }
+            }
+            if oldest <= from || oldest > end {
+                return Ok(());
+            }
+            end = oldest - 1;
+        }
+    }
+
+    async fn fetch_page(&self, request: &KlineRequest, start: i64, end: i64) -> Result<Vec<Bar>> {
+        let mut query = HashMap::new();
+        query.insert("category".to_owned(), request.category.to_string());
+        query.insert("symbol".to_owned(), request.symbol.clone());
+        query.insert("interval".to_owned(), request.interval.to_string());
+        query.insert("start".to_owned(), start.to_string());
+        query.insert("end".to_owned(), end.to_string());
+        query.insert("limit".to_owned(), PAGE_LIMIT.to_string());
+
+        let market = &self.market;
+        let bars = match request.kind {
+            KlineKind::Trade => into_bars(market.get_kline_typed(query).await?.list),
+            KlineKind::Mark => into_bars(market.get_mark_price_kline_typed(query).await?.list),
+            KlineKind::Index => into_bars(market.get_index_price_kline_typed(query).await?.list),
+            KlineKind::PremiumIndex => into_bars(
+                market
+                    .get_premium_index_price_kline_typed(query)
+                    .await?
+                    .list,
+            ),
+        };
+        Ok(bars)
+    }
+}
+
+fn into_bars<K: Into<Bar>>(klines: Vec<K>) -> Vec<Bar> {
+    klines.into_iter().map(Into::into).collect()
+}
+
+///
+/// Parts of `start..=end` to request: before the first stored bar, after the last one,
+/// and between stored bars further apart than one interval. Parts inside a range in
+/// `empty` are left out.
+///
+fn missing_ranges(
+    bars: &BTreeMap<i64, Bar>,
+    start: i64,
+    end: i64,
+    step: Option<i64>,
+    empty: &BTreeSet<(i64, i64)>,
+) -> Result<Vec<(i64, i64)>> {
+    let (first, last) = match (bars.keys().next(), bars.keys().next_back()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Ok(vec![(start, end)]),
+    };
+    let mut ranges = Vec::new();
+    if start < first {
+        ranges.push((start, end.min(first - 1)));
+    }
+    if end > last {
+        ranges.push((start.max(last + 1), end));
+    }
+    for gap in find_gaps(bars, start, end, step)? {
+        ranges.push((
+            gap.after.timestamp_millis() + 1,
+            gap.before.timestamp_millis() - 1,
+        ));
+    }
+    ranges.retain(|(from, to)| !empty.iter().any(|(a, b)| a <= from && to <= b));
+    Ok(ranges)
+}
+
+fn find_gaps(
+    bars: &BTreeMap<i64, Bar>,
+    start: i64,
+    end: i64,
+    step: Option<i64>,
+) -> Result<Vec<Gap>> {
+    let step = match step {
+        Some(step) => step,
+        None => return Ok(Vec::new()),
+    };
+    let starts: Vec<i64> = bars.range(start..=end).map(|(start, _)| *start).collect();
+    starts
+        .windows(2)
+        .filter(|pair| pair[1] - pair[0] > step)
+        .map(|pair| {
+            Ok(Gap {
+                after: millis(pair[0])?,
+                before: millis(pair[1])?,
+                missing: ((pair[1] - pair[0]) / step - 1) as u64,
+            })
+        })
+        .collect()
+}
+
+fn millis(value: i64) -> Result<DateTime<Utc>> {
+    Utc.timestamp_millis_opt(value).single().ok_or_else(|| {
+        AppError::InvalidInput(format!("Kline start time {} is out of range", value))
+    })
+}
+
+fn read_csv(path: &Path) -> Result<BTreeMap<i64, Bar>> {
+    let mut bars = BTreeMap::new();
+    if !path.exists() {
+        return Ok(bars);
+    }
+    let invalid = |line: &str| AppError::InvalidInput(format!("Malformed kline row {:?}", line));
+    for line in BufReader::new(File::open(path)?).lines().skip(1) {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() != 5 && fields.len() != 7 {
+            return Err(invalid(&line));
+        }
+        let start = i64::from_str(fields[0]).map_err(|_| invalid(&line))?;
+        let decimal = |index: usize| -> Result<Option<Decimal>> {
+            fields
+                .get(index)
+                .map(|value| Decimal::from_str(value).map_err(AppError::from))
+                .transpose()
+        };
+        let bar = Bar {
+            start_time: Utc
+                .timestamp_millis_opt(start)
+                .single()
+                .ok_or_else(|| invalid(&line))?,
+            open: decimal(1)?.unwrap_or_default(),
+            high: decimal(2)?.unwrap_or_default(),
+            low: decimal(3)?.unwrap_or_default(),
+            close: decimal(4)?.unwrap_or_default(),
+            volume: decimal(5)?,
+            turnover: decimal(6)?,
+        };
+        bars.insert(start, bar);
+    }
+    Ok(bars)
+}
+
+///
+/// Reads the `from,to` rows of a `.gaps` file.
+///
+fn read_ranges(path: &Path) -> Result<BTreeSet<(i64, i64)>> {
+    let mut ranges = BTreeSet::new();
+    if !path.exists() {
+        return Ok(ranges);
+    }
+    let invalid = |line: &str| AppError::InvalidInput(format!("Malformed gap row {:?}", line));
+    for line in BufReader::new(File::open(path)?).lines().skip(1) {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        let (from, to) = line.split_once(',').ok_or_else(|| invalid(&line))?;
+        let from = i64::from_str(from).map_err(|_| invalid(&line))?;
+        let to = i64::from_str(to).map_err(|_| invalid(&line))?;
+        ranges.insert((from, to));
+    }
+    Ok(ranges)
+}
+
+fn write_ranges(path: &Path, ranges: &BTreeSet<(i64, i64)>) -> Result<()> {
+    let tmp = path.with_extension("gaps.tmp");
+    let mut out = BufWriter::new(File::create(&tmp)?);
+    writeln!(out, "from,to")?;
+    for (from, to) in ranges {
+        writeln!(out, "{},{}", from, to)?;
+    }
+    out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    fs::rename(tmp, path)?;
+    Ok(())
+}
+
+///
+/// Writes to a temporary file first so an interrupted run keeps the previous file.
+///
+fn write_csv(path: &Path, bars: &BTreeMap<i64, Bar>, kind: KlineKind) -> Result<()> {
+    let tmp = path.with_extension("csv.tmp");
+    let mut out = BufWriter::new(File::create(&tmp)?);
+    if kind.has_volume() {
+        writeln!(out, "start_time,open,high,low,close,volume,turnover")?;
+    } else {
+        writeln!(out, "start_time,open,high,low,close")?;
+    }
+    for (start, bar) in bars {
+        write!(
+            out,
+            "{},{},{},{},{}",
+            start, bar.open, bar.high, bar.low, bar.close
+        )?;
+        if kind.has_volume() {
+            write!(
+                out,
+                ",{},{}",
+                bar.volume.unwrap_or_default(),
+                bar.turnover.unwrap_or_default()
+            )?;
+        }
+        writeln!(out)?;
+    }
+    out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    fs::rename(tmp, path)?;
+    Ok(())
+}
+
+fn write_parquet(path: &Path, bars: &BTreeMap<i64, Bar>, kind: KlineKind) -> Result<()> {
+    let mut columns = vec!["open", "high", "low", "close"];
+    if kind.has_volume() {
+        columns.extend(["volume", "turnover"]);
+    }
+    let mut schema =
+        String::from("message kline { REQUIRED INT64 start_time (TIMESTAMP(MILLIS,true));");
+    for column in &columns {
+        schema.push_str(&format!(" REQUIRED BYTE_ARRAY {} (UTF8);", column));
+    }
+    schema.push_str(" }");
+
+    let tmp = path.with_extension("parquet.tmp");
+    let mut writer = SerializedFileWriter::new(
+        File::create(&tmp)?,
+        Arc::new(parse_message_type(&schema)?),
+        Arc::new(WriterProperties::builder().build()),
+    )?;
+    let bars: Vec<&Bar> = bars.values().collect();
+    for chunk in bars.chunks(ROW_GROUP_SIZE) {
+        let mut row_group = writer.next_row_group()?;
+        let mut index = 0;
+        while let Some(mut column) = row_group.next_column()? {
+            if index == 0 {
+                let starts: Vec<i64> = chunk
+                    .iter()
+                    .map(|bar| bar.start_time.timestamp_millis())
+                    .collect();
+                column
+                    .typed::<Int64Type>()
+                    .write_batch(&starts, None, None)?;
+            } else {
+                let values: Vec<ByteArray> = chunk
+                    .iter()
+                    .map(|bar| {
+                        let value = match index {
+                            1 => Some(bar.open),
+                            2 => Some(bar.high),
+                            3 => Some(bar.low),
+                            4 => Some(bar.close),
+                            5 => bar.volume,
+                            _ => bar.turnover,
+                        };
+                        ByteArray::from(value.unwrap_or_default().to_string().as_str())
+                    })
+                    .collect();
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, None, None)?;
+            }
+            column.close()?;
+            index += 1;
+        }
+        row_group.close()?;
+    }
+    writer.close()?;
+    fs::rename(tmp, path)?;
+    Ok(())
+}
@@ -1,4 +1,5 @@
 #![allow(unused)]
+#![allow(clippy::empty_line_after_outer_attr, clippy::empty_docs)]
 use async_trait::async_trait;
 use std::{
     collections::{BTreeMap, HashMap},
@@ -6,15 +7,27 @@ use std::{
     sync::Arc,
 };
 
-use futures::Future;
+use futures::{stream::BoxStream, Future};
 use reqwest::Method;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
-use crate::endpoints::v5market;
+use crate::{
+    endpoints::v5market,
+    models::{
+        market::{
+            DeliveryPrice, FundingRate, HistoricalVolatility, InstrumentInfo, Insurance, Kline,
+            Klines, LongShortRatio, OpenInterestPage, Orderbook, PriceKline, PublicTrade,
+            RiskLimit, ServerTime, Tickers,
+        },
+        Page,
+    },
+};
 
 use super::{
     Result,
-    http_manager::{HttpManager, Manager}
+    http_manager::{HttpManager, Manager},
+    pagination::paginate,
 };
 
 #[async_trait]
@@ -82,6 +95,184 @@ pub trait Market {
         &self,
         query: HashMap<String, String>,
     ) -> Result<Value>;
+
+    async fn get_server_time(&self) -> Result<Value>;
+
+    async fn get_long_short_ratio(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    async fn get_rpi_orderbook(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    async fn get_order_price_limit(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    async fn get_index_price_components(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    async fn get_new_delivery_price(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    async fn get_spot_margin_data(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    async fn get_spot_margin_collateral(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    // Typed versions of the methods above. Prices and sizes are `Decimal`,
+    // timestamps `DateTime<Utc>`.
+
+    async fn get_kline_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Klines<Kline>> {
+        Ok(serde_json::from_value(self.get_kline(query).await?)?)
+    }
+
+    async fn get_mark_price_kline_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Klines<PriceKline>> {
+        Ok(serde_json::from_value(self.get_mark_price_kline(query).await?)?)
+    }
+
+    async fn get_index_price_kline_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Klines<PriceKline>> {
+        Ok(serde_json::from_value(self.get_index_price_kline(query).await?)?)
+    }
+
+    async fn get_premium_index_price_kline_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Klines<PriceKline>> {
+        Ok(serde_json::from_value(self.get_premium_index_price_kline(query).await?)?)
+    }
+
+    async fn get_instruments_info_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Page<InstrumentInfo>> {
+        Ok(serde_json::from_value(self.get_instruments_info(query).await?)?)
+    }
+
+    async fn get_orderbook_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Orderbook> {
+        Ok(serde_json::from_value(self.get_orderbook(query).await?)?)
+    }
+
+    async fn get_tickers_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Tickers> {
+        Ok(serde_json::from_value(self.get_tickers(query).await?)?)
+    }
+
+    async fn get_funding_rate_history_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Page<FundingRate>> {
+        Ok(serde_json::from_value(self.get_funding_rate_history(query).await?)?)
+    }
+
+    async fn get_public_trade_history_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Page<PublicTrade>> {
+        Ok(serde_json::from_value(self.get_public_trade_history(query).await?)?)
+    }
+
+    async fn get_open_interest_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<OpenInterestPage> {
+        Ok(serde_json::from_value(self.get_open_interest(query).await?)?)
+    }
+
+    async fn get_historical_volatility_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Vec<HistoricalVolatility>> {
+        Ok(serde_json::from_value(self.get_historical_volatility(query).await?)?)
+    }
+
+    async fn get_insurance_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Insurance> {
+        Ok(serde_json::from_value(self.get_insurance(query).await?)?)
+    }
+
+    async fn get_risk_limit_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Page<RiskLimit>> {
+        Ok(serde_json::from_value(self.get_risk_limit(query).await?)?)
+    }
+
+    async fn get_option_delivery_price_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Page<DeliveryPrice>> {
+        Ok(serde_json::from_value(self.get_option_delivery_price(query).await?)?)
+    }
+
+    async fn get_server_time_typed(&self) -> Result<ServerTime> {
+        Ok(serde_json::from_value(self.get_server_time().await?)?)
+    }
+
+    async fn get_long_short_ratio_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Page<LongShortRatio>> {
+        Ok(serde_json::from_value(self.get_long_short_ratio(query).await?)?)
+    }
+
+    ///
+    /// Every instrument from `get_instruments_info`, across all of its pages.
+    /// Instruments are paged per `category`, so each category is its own stream.
+    ///
+    fn get_instruments_info_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_instruments_info(query))
+    }
+
+    ///
+    /// Every long/short ratio sample from `get_long_short_ratio`, across all of its pages.
+    ///
+    fn get_long_short_ratio_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_long_short_ratio(query))
+    }
 }
 
 pub struct MarketHTTP {
@@ -373,14 +564,16 @@ impl Market for MarketHTTP {
             )
             .await
     }
-    /// Query Bybit insurance pool data (BTC/USDT/USDC etc).
-    ///     The data is updated every 24 hours.
-
-    ///     Returns:
-    ///         Request results as HashMap.
-
-    ///     Additional information:
-    ///         https://bybit-exchange.github.io/docs/v5/market/insurance
+    /// Query the delivery price of options and futures.
+    ///
+    /// Required args:
+    ///     category (string): Product type. linear,inverse,option
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/market/delivery-price
     async fn get_option_delivery_price(
         &self,
         query: HashMap<String, String>,
@@ -394,4 +587,189 @@ impl Market for MarketHTTP {
             )
             .await
     }
+
+    /// Get the Bybit server time.
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/market/time
+    async fn get_server_time(&self) -> Result<Value> {
+        self.http_manager
+            .submit_request(
+                Method::GET,
+                &v5market::MarketEnum::GetServerTime.to_string(),
+                HashMap::new(),
+                false,
+            )
+            .await
+    }
+
+    /// Query the ratio of users with long and short positions.
+    ///
+    /// Required args:
+    ///     category (string): Product type. linear (USDT contract),inverse
+    ///     symbol (string): Symbol name
+    ///     period (string): Data recording period. 5min,15min,30min,1h,4h,1d
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/market/long-shortratio
+    async fn get_long_short_ratio(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        self.http_manager
+            .submit_request(
+                Method::GET,
+                &v5market::MarketEnum::GetLongShortRatio.to_string(),
+                query,
+                true,
+            )
+            .await
+    }
+
+    /// Query orderbook data including Retail Price Improvement (RPI) orders.
+    ///
+    /// Required args:
+    ///     category (string): Product type. spot, linear, inverse
+    ///     symbol (string): Symbol name
+    ///     limit (string): Limit size for each bid and ask. 1-50
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/market/rpi-orderbook
+    async fn get_rpi_orderbook(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        self.http_manager
+            .submit_request(
+                Method::GET,
+                &v5market::MarketEnum::GetRpiOrderbook.to_string(),
+                query,
+                true,
+            )
+            .await
+    }
+
+    /// Query the highest buy price and lowest sell price currently allowed for a symbol.
+    ///
+    /// Required args:
+    ///     symbol (string): Symbol name
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/market/order-price-limit
+    async fn get_order_price_limit(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        self.http_manager
+            .submit_request(
+                Method::GET,
+                &v5market::MarketEnum::GetOrderPriceLimit.to_string(),
+                query,
+                true,
+            )
+            .await
+    }
+
+    /// Query the exchanges and weights that make up an index price.
+    ///
+    /// Required args:
+    ///     indexName (string): Index name, like BTCUSDT
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/market/index-components
+    async fn get_index_price_components(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        self.http_manager
+            .submit_request(
+                Method::GET,
+                &v5market::MarketEnum::GetIndexPriceComponents.to_string(),
+                query,
+                true,
+            )
+            .await
+    }
+
+    /// Query the latest delivery prices of options.
+    ///
+    /// Required args:
+    ///     category (string): Product type. option
+    ///     baseCoin (string): Base coin
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/market/new-delivery-price
+    async fn get_new_delivery_price(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        self.http_manager
+            .submit_request(
+                Method::GET,
+                &v5market::MarketEnum::GetNewDeliveryPrice.to_string(),
+                query,
+                true,
+            )
+            .await
+    }
+
+    /// Query the VIP margin data: borrowable coins, hourly borrow rates, borrow limits and leverage.
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/spot-margin-uta/vip-margin
+    async fn get_spot_margin_data(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        self.http_manager
+            .submit_request(
+                Method::GET,
+                &v5market::MarketEnum::GetSpotMarginData.to_string(),
+                query,
+                true,
+            )
+            .await
+    }
+
+    /// Query the tiered collateral ratio of unified account spot margin coins.
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/spot-margin-uta/tier-collateral-ratio
+    async fn get_spot_margin_collateral(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        self.http_manager
+            .submit_request(
+                Method::GET,
+                &v5market::MarketEnum::GetSpotMarginCollateral.to_string(),
+                query,
+                true,
+            )
+            .await
+    }
 }
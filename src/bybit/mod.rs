@@ -2,13 +2,25 @@ pub mod account;
 pub mod asset;
 pub mod broker;
 pub mod http_manager;
+pub mod instruments;
+#[cfg(feature = "archive")]
+pub mod kline_archive;
 pub mod market;
+pub mod orderbook;
+pub mod pagination;
 pub mod position;
+pub mod rate_limit;
+pub mod response;
+pub mod signer;
 pub mod spot_leverage_token;
 pub mod spot_margin_trade;
+pub mod time_sync;
 pub mod trade;
+pub mod trade_ws;
 pub mod user;
+pub mod websocket_stream;
 
+use crate::errors::app_error::AppError;
 
-type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+type Error = AppError;
 type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,367 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+
+use rust_decimal::Decimal;
+use serde_derive::Deserialize;
+
+use super::{
+    market::{Market, MarketHTTP},
+    websocket_stream::{StreamEvent, StreamHandle, TopicMessage},
+    AppError, Result,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+///
+/// Outcome of applying a message to an `OrderBook`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookUpdate {
+    /// A snapshot replaced the book.
+    Snapshot,
+    /// A delta was merged.
+    Delta,
+    /// The message was older than the book and was skipped.
+    Outdated,
+    /// Update ids skipped ahead. The book is out of sync until the next snapshot.
+    Gap { expected: u64, received: u64 },
+    /// A delta arrived while the book is waiting for a snapshot and was skipped.
+    AwaitingSnapshot,
+}
+
+///
+/// The `data` object of orderbook messages and of `/v5/market/orderbook`.
+///
+#[derive(Debug, Clone, Deserialize)]
+pub struct BookData {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "b")]
+    pub bids: Vec<[String; 2]>,
+    #[serde(rename = "a")]
+    pub asks: Vec<[String; 2]>,
+    #[serde(rename = "u")]
+    pub update_id: u64,
+    #[serde(default)]
+    pub seq: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SyncState {
+    Synced,
+    AwaitingSnapshot,
+    /// Loaded from REST; the next delta newer than this `seq` re-anchors the update ids.
+    AwaitingDeltaAfter(u64),
+}
+
+///
+/// Local order book built from `orderbook.{depth}.{symbol}` snapshots and deltas.
+/// Deltas must carry contiguous `u` update ids; a skipped id marks the book out of sync.
+///
+#[derive(Debug, Clone)]
+pub struct OrderBook {
+    symbol: String,
+    bids: BTreeMap<Decimal, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+    update_id: u64,
+    seq: u64,
+    ts: u64,
+    state: SyncState,
+}
+
+impl OrderBook {
+    pub fn new(symbol: &str) -> Self {
+        OrderBook {
+            symbol: symbol.to_owned(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            update_id: 0,
+            seq: 0,
+            ts: 0,
+            state: SyncState::AwaitingSnapshot,
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn update_id(&self) -> u64 {
+        self.update_id
+    }
+
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Exchange timestamp of the last applied message, in milliseconds.
+    pub fn timestamp(&self) -> u64 {
+        self.ts
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.state != SyncState::AwaitingSnapshot
+    }
+
+    ///
+    /// Drops the current levels and waits for the next snapshot.
+    ///
+    pub fn invalidate(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+        self.state = SyncState::AwaitingSnapshot;
+    }
+
+    ///
+    /// Applies an orderbook stream message.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/websocket/public/orderbook
+    pub fn apply(&mut self, message: &TopicMessage) -> Result<BookUpdate> {
+        let data: BookData = serde_json::from_value(message.data.clone())?;
+        if data.symbol != self.symbol {
+            return Err(AppError::InvalidInput(format!(
+                "Order book for {} received data for {}",
+                self.symbol, data.symbol
+            )));
+        }
+        let ts = message.ts.unwrap_or_default();
+        match message.kind.as_deref() {
+            Some("snapshot") => {
+                self.load_snapshot(&data, ts)?;
+                Ok(BookUpdate::Snapshot)
+            }
+            Some("delta") => self.apply_delta(&data, ts),
+            other => Err(AppError::InvalidInput(format!(
+                "Unknown orderbook message type {:?}",
+                other
+            ))),
+        }
+    }
+
+    ///
+    /// Replaces the book with a snapshot.
+    ///
+    pub fn load_snapshot(&mut self, data: &BookData, ts: u64) -> Result<()> {
+        self.bids = parse_levels(&data.bids)?;
+        self.asks = parse_levels(&data.asks)?;
+        self.update_id = data.update_id;
+        self.seq = data.seq;
+        self.ts = ts;
+        self.state = SyncState::Synced;
+        Ok(())
+    }
+
+    ///
+    /// Merges a delta, checking that its `u` follows the last applied one.
+    ///
+    pub fn apply_delta(&mut self, data: &BookData, ts: u64) -> Result<BookUpdate> {
+        match self.state {
+            SyncState::AwaitingSnapshot => return Ok(BookUpdate::AwaitingSnapshot),
+            SyncState::AwaitingDeltaAfter(seq) => {
+                if data.seq <= seq {
+                    return Ok(BookUpdate::Outdated);
+                }
+            }
+            SyncState::Synced => {
+                if data.update_id <= self.update_id {
+                    return Ok(BookUpdate::Outdated);
+                }
+                if data.update_id != self.update_id + 1 {
+                    let gap = BookUpdate::Gap {
+                        expected: self.update_id + 1,
+                        received: data.update_id,
+                    };
+                    self.invalidate();
+                    return Ok(gap);
+                }
+            }
+        }
+
+        merge_levels(&mut self.bids, &data.bids)?;
+        merge_levels(&mut self.asks, &data.asks)?;
+        self.update_id = data.update_id;
+        self.seq = data.seq;
+        self.ts = ts;
+        self.state = SyncState::Synced;
+        Ok(BookUpdate::Delta)
+    }
+
+    ///
+    /// Reloads the book from `/v5/market/orderbook`. Its `u` cannot be compared with the
+    /// stream's, so stream deltas are matched on `seq` instead: those not newer than the
+    /// snapshot's `seq` are dropped as `Outdated`, the first newer one is merged without a
+    /// `u` check, and the `u` continuity checks resume from it.
+    ///
+    pub async fn resync_from_rest(
+        &mut self,
+        market: &MarketHTTP,
+        category: &str,
+        limit: u32,
+    ) -> Result<()> {
+        let mut query = HashMap::new();
+        query.insert("category".to_owned(), category.to_owned());
+        query.insert("symbol".to_owned(), self.symbol.clone());
+        query.insert("limit".to_owned(), limit.to_string());
+        let result = market.get_orderbook(query).await?;
+        let data: BookData = serde_json::from_value(result.clone())?;
+        let ts = result["ts"].as_u64().unwrap_or_default();
+        self.load_snapshot(&data, ts)?;
+        self.state = SyncState::AwaitingDeltaAfter(data.seq);
+        Ok(())
+    }
+
+    ///
+    /// Bids from best (highest) to worst.
+    ///
+    pub fn bids(&self) -> impl Iterator<Item = (&Decimal, &Decimal)> {
+        self.bids.iter().rev()
+    }
+
+    ///
+    /// Asks from best (lowest) to worst.
+    ///
+    pub fn asks(&self) -> impl Iterator<Item = (&Decimal, &Decimal)> {
+        self.asks.iter()
+    }
+
+    /// Best bid as `(price, size)`.
+    pub fn best_bid(&self) -> Option<(Decimal, Decimal)> {
+        self.bids.iter().next_back().map(|(p, s)| (*p, *s))
+    }
+
+    /// Best ask as `(price, size)`.
+    pub fn best_ask(&self) -> Option<(Decimal, Decimal)> {
+        self.asks.iter().next().map(|(p, s)| (*p, *s))
+    }
+
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()?.0 - self.best_bid()?.0)
+    }
+
+    pub fn mid(&self) -> Option<Decimal> {
+        Some((self.best_ask()?.0 + self.best_bid()?.0) / Decimal::TWO)
+    }
+
+    ///
+    /// Size resting at `price` on `side`, zero if there is no level.
+    ///
+    pub fn depth_at(&self, side: BookSide, price: Decimal) -> Decimal {
+        let levels = match side {
+            BookSide::Bid => &self.bids,
+            BookSide::Ask => &self.asks,
+        };
+        levels.get(&price).copied().unwrap_or_default()
+    }
+}
+
+fn parse_levels(levels: &[[String; 2]]) -> Result<BTreeMap<Decimal, Decimal>> {
+    let mut book = BTreeMap::new();
+    merge_levels(&mut book, levels)?;
+    Ok(book)
+}
+
+fn merge_levels(book: &mut BTreeMap<Decimal, Decimal>, levels: &[[String; 2]]) -> Result<()> {
+    for [price, size] in levels {
+        let price = Decimal::from_str(price)?;
+        let size = Decimal::from_str(size)?;
+        if size.is_zero() {
+            book.remove(&price);
+        } else {
+            book.insert(price, size);
+        }
+    }
+    Ok(())
+}
+
+///
+/// Keeps an `OrderBook` in sync with a public stream.
+/// On a gap it reloads from REST when a `MarketHTTP` is set, otherwise it resubscribes
+/// the topic to get a fresh snapshot. Reconnects invalidate the book until the new
+/// snapshot arrives.
+///
+pub struct SyncedOrderBook {
+    book: OrderBook,
+    topic: String,
+    handle: StreamHandle,
+    rest: Option<(MarketHTTP, String)>,
+}
+
+impl SyncedOrderBook {
+    pub fn new(depth: u32, symbol: &str, handle: StreamHandle) -> Self {
+        SyncedOrderBook {
+            book: OrderBook::new(symbol),
+            topic: format!("orderbook.{}.{}", depth, symbol),
+            handle,
+            rest: None,
+        }
+    }
+
+    ///
+    /// Resync from `/v5/market/orderbook` for `category` instead of resubscribing.
+    ///
+    pub fn with_rest_resync(mut self, market: MarketHTTP, category: &str) -> Self {
+        self.rest = Some((market, category.to_owned()));
+        self
+    }
+
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    ///
+    /// Subscribes the handle to the book's topic.
+    ///
+    pub fn subscribe(&self) -> Result<()> {
+        self.handle.subscribe(vec![self.topic.clone()])
+    }
+
+    ///
+    /// Feeds a stream event to the book. Events for other topics return `Ok(None)`.
+    ///
+    pub async fn handle_event(&mut self, event: &StreamEvent) -> Result<Option<BookUpdate>> {
+        match event {
+            StreamEvent::Message(message) if message.topic == self.topic => {
+                let update = self.book.apply(message)?;
+                if let BookUpdate::Gap { .. } = update {
+                    self.resync().await?;
+                }
+                Ok(Some(update))
+            }
+            StreamEvent::Reconnected => {
+                // the resubscribe on reconnect delivers a fresh snapshot
+                self.book.invalidate();
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn resync(&mut self) -> Result<()> {
+        match &self.rest {
+            Some((market, category)) => {
+                let depth = self
+                    .topic
+                    .split('.')
+                    .nth(1)
+                    .and_then(|depth| depth.parse().ok())
+                    .unwrap_or(50);
+                self.book.resync_from_rest(market, category, depth).await
+            }
+            None => {
+                self.handle.unsubscribe(vec![self.topic.clone()])?;
+                self.handle.subscribe(vec![self.topic.clone()])
+            }
+        }
+    }
+}
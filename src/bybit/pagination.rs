@@ -0,0 +1,165 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use futures::{
+    stream::{self, BoxStream},
+    Future, StreamExt, TryStreamExt,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::{AppError, Result};
+
+/// Keys record endpoints put their items under: `list` for most, `rows` for deposit
+/// and withdrawal records, `orderBody` for coin exchange records.
+const ITEM_KEYS: [&str; 3] = ["list", "rows", "orderBody"];
+
+/// Widest `startTime`..`endTime` range of executions, orders, closed PnL,
+/// the transaction log and transfer records.
+pub const SEVEN_DAYS: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Widest range of deposit, withdrawal and borrow records.
+pub const THIRTY_DAYS: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+///
+/// Turns a cursor paged endpoint into a stream of its items. `fetch` is called with
+/// `query`, then again with `cursor` set to each `nextPageCursor` until it is empty.
+/// Pages are only requested as the stream is polled, and every request goes through
+/// the `HttpManager` rate limiter. Items are deserialized into `T`: `Value` keeps them
+/// as JSON, a model such as `Execution` types them.
+///
+pub fn paginate<'a, T, F, Fut>(query: HashMap<String, String>, fetch: F) -> BoxStream<'a, Result<T>>
+where
+    T: DeserializeOwned + Send + 'a,
+    F: Fn(HashMap<String, String>) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<Value>> + Send + 'a,
+{
+    stream::try_unfold((fetch, Some(query)), step)
+        .map_ok(|items: Vec<T>| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+}
+
+///
+/// Fetches the page of `query`, if there is one, and returns its items with the state
+/// for the next page.
+///
+async fn step<T, F, Fut>(
+    (fetch, query): (F, Option<HashMap<String, String>>),
+) -> Result<Option<(Vec<T>, (F, Option<HashMap<String, String>>))>>
+where
+    T: DeserializeOwned,
+    F: Fn(HashMap<String, String>) -> Fut,
+    Fut: Future<Output = Result<Value>>,
+{
+    let mut query = match query {
+        Some(query) => query,
+        None => return Ok(None),
+    };
+    let (items, cursor) = split_page(fetch(query.clone()).await?)?;
+    let previous = query.get("cursor").map(String::as_str);
+    if items.is_empty() || cursor.is_empty() || previous == Some(cursor.as_str()) {
+        return Ok(Some((items, (fetch, None))));
+    }
+    query.insert("cursor".to_owned(), cursor);
+    Ok(Some((items, (fetch, Some(query)))))
+}
+
+fn split_page<T: DeserializeOwned>(mut result: Value) -> Result<(Vec<T>, String)> {
+    let cursor = result["nextPageCursor"]
+        .as_str()
+        .unwrap_or_default()
+        .to_owned();
+    let items = ITEM_KEYS
+        .iter()
+        .find_map(|key| result.get_mut(*key).map(Value::take))
+        .unwrap_or_else(|| Value::Array(Vec::new()));
+    Ok((serde_json::from_value(items)?, cursor))
+}
+
+///
+/// `paginate` over an arbitrary `start`..=`end` range of an endpoint that only accepts
+/// ranges up to `window`. The range is split into windows, newest first, and each is
+/// paged through with `startTime` and `endTime` set. Items seen in the previous window
+/// are skipped, keyed by the `key` field or, without one, the whole item.
+///
+pub fn paginate_range<'a, T, F, Fut>(
+    query: HashMap<String, String>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    window: Duration,
+    key: Option<&'static str>,
+    fetch: F,
+) -> BoxStream<'a, Result<T>>
+where
+    T: DeserializeOwned + Send + 'a,
+    F: Fn(HashMap<String, String>) -> Fut + Clone + Send + 'a,
+    Fut: Future<Output = Result<Value>> + Send + 'a,
+{
+    let windows = match split_range(start, end, window) {
+        Ok(windows) => windows,
+        Err(e) => return stream::once(async { Err(e) }).boxed(),
+    };
+
+    let mut current = (usize::MAX, HashSet::new());
+    let mut previous = HashSet::new();
+    stream::iter(windows.into_iter().enumerate())
+        .flat_map(move |(index, (start, end))| {
+            let mut query = query.clone();
+            query.insert("startTime".to_owned(), start.to_string());
+            query.insert("endTime".to_owned(), end.to_string());
+            paginate::<Value, _, _>(query, fetch.clone()).map_ok(move |item| (index, item))
+        })
+        .try_filter_map(move |(index, item)| {
+            if index != current.0 {
+                previous = std::mem::take(&mut current.1);
+                current.0 = index;
+            }
+            let id = match key.and_then(|key| item.get(key)) {
+                Some(Value::String(id)) => id.clone(),
+                Some(id) => id.to_string(),
+                None => item.to_string(),
+            };
+            let seen = previous.contains(&id);
+            let fresh = current.1.insert(id) && !seen;
+            let item = if fresh {
+                serde_json::from_value(item)
+                    .map(Some)
+                    .map_err(AppError::from)
+            } else {
+                Ok(None)
+            };
+            async move { item }
+        })
+        .boxed()
+}
+
+///
+/// Inclusive millisecond windows no wider than `window`, newest first.
+///
+pub fn split_range(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    window: Duration,
+) -> Result<Vec<(i64, i64)>> {
+    let (start, mut end) = (start.timestamp_millis(), end.timestamp_millis());
+    let width = window.as_millis() as i64;
+    if start > end || width <= 0 {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid time range {}..{} for a {:?} window",
+            start, end, window
+        )));
+    }
+    let mut windows = Vec::new();
+    loop {
+        let window_start = (end - width + 1).max(start);
+        windows.push((window_start, end));
+        if window_start == start {
+            return Ok(windows);
+        }
+        end = window_start - 1;
+    }
+}
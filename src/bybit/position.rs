@@ -1,4 +1,5 @@
 #![allow(unused)]
+#![allow(clippy::empty_line_after_outer_attr, clippy::empty_docs)]
 use async_trait::async_trait;
 use std::{
     collections::{BTreeMap, HashMap},
@@ -6,14 +7,23 @@ use std::{
     sync::Arc,
 };
 
-use futures::Future;
+use chrono::{DateTime, Utc};
+use futures::{stream::BoxStream, Future};
 use reqwest::Method;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
-use crate::endpoints::v5position;
+use crate::{
+    endpoints::v5position,
+    models::{
+        position::{ClosedPnl, Execution, PositionInfo},
+        Page,
+    },
+};
 
 use super::{
     http_manager::{HttpManager, Manager},
+    pagination::{paginate, paginate_range, SEVEN_DAYS},
     Result,
 };
 
@@ -38,6 +48,108 @@ pub trait Position {
     async fn get_executions(&self, query: HashMap<String, String>) -> Result<Value>;
 
     async fn get_closed_pnl(&self, query: HashMap<String, String>) -> Result<Value>;
+
+    async fn get_position_typed(&self, query: HashMap<String, String>) -> Result<Page<PositionInfo>> {
+        Ok(serde_json::from_value(self.get_position(query).await?)?)
+    }
+
+    async fn get_executions_typed(&self, query: HashMap<String, String>) -> Result<Page<Execution>> {
+        Ok(serde_json::from_value(self.get_executions(query).await?)?)
+    }
+
+    async fn get_closed_pnl_typed(&self, query: HashMap<String, String>) -> Result<Page<ClosedPnl>> {
+        Ok(serde_json::from_value(self.get_closed_pnl(query).await?)?)
+    }
+
+    ///
+    /// Every position from `get_position`, across all of its pages.
+    ///
+    fn get_position_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_position(query))
+    }
+
+    ///
+    /// Every execution from `get_executions`, across all of its pages.
+    ///
+    fn get_executions_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_executions(query))
+    }
+
+    ///
+    /// Every closed PnL record from `get_closed_pnl`, across all of its pages.
+    ///
+    fn get_closed_pnl_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_closed_pnl(query))
+    }
+
+    ///
+    /// Executions from `get_executions` between `start` and `end`, requested in seven day
+    /// windows.
+    ///
+    fn get_executions_range<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate_range(
+            query,
+            start,
+            end,
+            SEVEN_DAYS,
+            Some("execId"),
+            move |query| self.get_executions(query),
+        )
+    }
+
+    ///
+    /// Closed PnL records from `get_closed_pnl` between `start` and `end`, requested in
+    /// seven day windows.
+    ///
+    fn get_closed_pnl_range<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate_range(
+            query,
+            start,
+            end,
+            SEVEN_DAYS,
+            Some("orderId"),
+            move |query| self.get_closed_pnl(query),
+        )
+    }
 }
 
 pub struct PositionHTTP {
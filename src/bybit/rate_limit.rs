@@ -0,0 +1,273 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use reqwest::header::HeaderMap;
+
+use crate::errors::app_error::AppError;
+
+use super::{time_sync::TimeSync, Result};
+
+const LIMIT_HEADER: &str = "X-Bapi-Limit";
+const LIMIT_STATUS_HEADER: &str = "X-Bapi-Limit-Status";
+const LIMIT_RESET_HEADER: &str = "X-Bapi-Limit-Reset-Timestamp";
+
+///
+/// Endpoint groups with a documented rate limit.
+/// Private limits apply per UID and per endpoint; public market data shares one per-IP limit.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/rate-limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointGroup {
+    /// `/v5/order/create`, `amend`, `cancel` and `cancel-all`.
+    Order,
+    /// `/v5/order/create-batch`, `amend-batch` and `cancel-batch`.
+    BatchOrder,
+    /// Open orders, order history and executions.
+    OrderQuery,
+    Position,
+    Account,
+    Asset,
+    User,
+    /// Unauthenticated market data, limited per IP.
+    Public,
+    Other,
+}
+
+impl EndpointGroup {
+    pub fn of(path: &str, auth: bool) -> Self {
+        // market data is public even when the request is signed
+        if !auth || path.starts_with("/v5/market/") {
+            return EndpointGroup::Public;
+        }
+        match path {
+            "/v5/order/create"
+            | "/v5/order/amend"
+            | "/v5/order/cancel"
+            | "/v5/order/cancel-all" => EndpointGroup::Order,
+            "/v5/order/create-batch" | "/v5/order/amend-batch" | "/v5/order/cancel-batch" => {
+                EndpointGroup::BatchOrder
+            }
+            "/v5/order/realtime" | "/v5/order/history" | "/v5/execution/list" => {
+                EndpointGroup::OrderQuery
+            }
+            _ if path.starts_with("/v5/position/") => EndpointGroup::Position,
+            _ if path.starts_with("/v5/account/") => EndpointGroup::Account,
+            _ if path.starts_with("/v5/asset/") => EndpointGroup::Asset,
+            _ if path.starts_with("/v5/user/") => EndpointGroup::User,
+            _ => EndpointGroup::Other,
+        }
+    }
+
+    ///
+    /// Documented limit as `(requests, window)`. The smallest value across categories is used.
+    ///
+    pub fn default_limit(&self) -> (u32, Duration) {
+        let second = Duration::from_secs(1);
+        match self {
+            EndpointGroup::Order | EndpointGroup::BatchOrder => (10, second),
+            EndpointGroup::OrderQuery => (50, second),
+            EndpointGroup::Position => (10, second),
+            EndpointGroup::Account => (10, second),
+            EndpointGroup::Asset | EndpointGroup::User => (5, second),
+            EndpointGroup::Public => (600, Duration::from_secs(5)),
+            EndpointGroup::Other => (10, second),
+        }
+    }
+}
+
+///
+/// What to do with a call when its window is used up.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitMode {
+    /// Wait until the window resets, then send.
+    Queue,
+    /// Fail with `AppError::Throttled` without sending.
+    Reject,
+}
+
+#[derive(Debug)]
+struct Window {
+    limit: u32,
+    remaining: u32,
+    length: Duration,
+    reset_at: Instant,
+}
+
+impl Window {
+    fn new(limit: u32, length: Duration, now: Instant) -> Self {
+        Window {
+            limit,
+            remaining: limit,
+            length,
+            reset_at: now + length,
+        }
+    }
+
+    /// Takes one request from the window, or returns how long until it resets.
+    fn take(&mut self, now: Instant) -> std::result::Result<(), Duration> {
+        if now >= self.reset_at {
+            self.remaining = self.limit;
+            self.reset_at = now + self.length;
+        }
+        if self.remaining == 0 {
+            return Err(self.reset_at - now);
+        }
+        self.remaining -= 1;
+        Ok(())
+    }
+}
+
+///
+/// Client-side limiter shared by every client of an `HttpManager`.
+/// Each endpoint starts from its group's documented limit and follows the
+/// `X-Bapi-Limit-*` headers of its replies once they arrive.
+///
+#[derive(Debug)]
+pub struct RateLimiter {
+    mode: RateLimitMode,
+    enabled: bool,
+    limits: HashMap<EndpointGroup, (u32, Duration)>,
+    windows: Mutex<HashMap<String, Window>>,
+    time_sync: Arc<TimeSync>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter {
+            mode: RateLimitMode::Queue,
+            enabled: true,
+            limits: HashMap::new(),
+            windows: Mutex::new(HashMap::new()),
+            time_sync: Arc::new(TimeSync::new()),
+        }
+    }
+}
+
+impl RateLimiter {
+    pub fn new(mode: RateLimitMode) -> Self {
+        RateLimiter {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// A limiter that lets every call through.
+    ///
+    pub fn disabled() -> Self {
+        RateLimiter {
+            enabled: false,
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// Overrides the limit of a group, e.g. for a higher VIP tier.
+    ///
+    pub fn with_limit(mut self, group: EndpointGroup, requests: u32, window: Duration) -> Self {
+        self.limits.insert(group, (requests, window));
+        self
+    }
+
+    ///
+    /// Clock used to read `X-Bapi-Limit-Reset-Timestamp`, which is in server time.
+    /// `HttpManager` shares its own, so the reset follows the synced offset.
+    ///
+    pub fn with_time_sync(mut self, time_sync: Arc<TimeSync>) -> Self {
+        self.time_sync = time_sync;
+        self
+    }
+
+    pub fn mode(&self) -> RateLimitMode {
+        self.mode
+    }
+
+    ///
+    /// Waits for, or in `Reject` mode fails without, a free slot for `path`.
+    ///
+    pub async fn acquire(&self, path: &str, auth: bool) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        loop {
+            let wait = {
+                let mut windows = self.windows.lock().unwrap();
+                let now = Instant::now();
+                let window = windows
+                    .entry(Self::key(path, auth))
+                    .or_insert_with(|| self.new_window(path, auth, now));
+                match window.take(now) {
+                    Ok(()) => return Ok(()),
+                    Err(wait) => wait,
+                }
+            };
+            match self.mode {
+                RateLimitMode::Queue => tokio::time::sleep(wait).await,
+                RateLimitMode::Reject => {
+                    return Err(AppError::Throttled {
+                        path: path.to_owned(),
+                        retry_after: wait,
+                    })
+                }
+            }
+        }
+    }
+
+    ///
+    /// Adjusts the window of `path` from the reply's `X-Bapi-Limit`,
+    /// `X-Bapi-Limit-Status` and `X-Bapi-Limit-Reset-Timestamp` headers.
+    /// Replies without them leave the window unchanged.
+    ///
+    pub fn update(&self, path: &str, auth: bool, headers: &HeaderMap) {
+        if !self.enabled {
+            return;
+        }
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+        let remaining = match header(LIMIT_STATUS_HEADER) {
+            Some(remaining) => remaining as u32,
+            None => return,
+        };
+
+        let mut windows = self.windows.lock().unwrap();
+        let now = Instant::now();
+        let window = windows
+            .entry(Self::key(path, auth))
+            .or_insert_with(|| self.new_window(path, auth, now));
+        if let Some(limit) = header(LIMIT_HEADER) {
+            window.limit = limit as u32;
+        }
+        // other calls may have been counted locally since the server took its count
+        window.remaining = window.remaining.min(remaining);
+        if let Some(reset_ms) = header(LIMIT_RESET_HEADER) {
+            let now_ms = self.time_sync.timestamp().unwrap_or_default() as u64;
+            window.reset_at = now + Duration::from_millis(reset_ms.saturating_sub(now_ms));
+        }
+    }
+
+    fn key(path: &str, auth: bool) -> String {
+        match EndpointGroup::of(path, auth) {
+            EndpointGroup::Public => "public".to_owned(),
+            _ => path.to_owned(),
+        }
+    }
+
+    fn new_window(&self, path: &str, auth: bool, now: Instant) -> Window {
+        let group = EndpointGroup::of(path, auth);
+        let (limit, length) = self
+            .limits
+            .get(&group)
+            .copied()
+            .unwrap_or_else(|| group.default_limit());
+        Window::new(limit, length, now)
+    }
+}
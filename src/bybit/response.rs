@@ -0,0 +1,67 @@
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::errors::app_error::AppError;
+
+///
+/// The `{retCode, retMsg, result, retExtInfo, time}` envelope of every v5 REST reply.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/error
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiResponse<T = Value> {
+    pub ret_code: i64,
+    #[serde(default)]
+    pub ret_msg: String,
+    #[serde(default)]
+    pub result: T,
+    #[serde(default)]
+    pub ret_ext_info: Value,
+    /// Server time of the reply, in milliseconds.
+    #[serde(default)]
+    pub time: u64,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn is_success(&self) -> bool {
+        self.ret_code == 0
+    }
+
+    ///
+    /// Returns `result`, or the error matching `retCode` when it is not 0.
+    ///
+    pub fn into_result(self) -> Result<T, AppError> {
+        if self.is_success() {
+            return Ok(self.result);
+        }
+        Err(AppError::from_ret_code(
+            self.ret_code,
+            self.ret_msg,
+            self.ret_ext_info,
+        ))
+    }
+}
+
+impl ApiResponse<Value> {
+    ///
+    /// Parses a raw reply body into the envelope.
+    ///
+    pub fn from_value(body: Value) -> Result<Self, AppError> {
+        Ok(serde_json::from_value(body)?)
+    }
+
+    ///
+    /// Deserializes `result` into `R`, keeping the rest of the envelope.
+    ///
+    pub fn parse<R: DeserializeOwned>(self) -> Result<ApiResponse<R>, AppError> {
+        Ok(ApiResponse {
+            ret_code: self.ret_code,
+            ret_msg: self.ret_msg,
+            result: serde_json::from_value(self.result)?,
+            ret_ext_info: self.ret_ext_info,
+            time: self.time,
+        })
+    }
+}
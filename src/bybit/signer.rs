@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use openssl::{
+    hash::MessageDigest,
+    pkey::{PKey, Private},
+};
+use ring::hmac;
+
+use crate::errors::app_error::AppError;
+
+use super::Result;
+
+///
+/// Signs request payloads for the REST `Manager` and WebSocket auth.
+/// REST signs `{timestamp}{api_key}{recv_window}{params}`; WebSocket auth signs `GET/realtime{expires}`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/guide#create-a-request
+pub trait Signer: Send + Sync {
+    fn sign(&self, payload: &str) -> Result<String>;
+
+    ///
+    /// Value of the `X-BAPI-SIGN-TYPE` header, `None` to leave it out.
+    ///
+    fn sign_type(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+///
+/// HMAC-SHA256 with the API secret, hex encoded. Used for system-generated keys.
+///
+pub struct HmacSigner {
+    key: hmac::Key,
+    empty: bool,
+}
+
+impl HmacSigner {
+    pub fn new(api_secret: &str) -> Self {
+        HmacSigner {
+            key: hmac::Key::new(hmac::HMAC_SHA256, api_secret.as_bytes()),
+            empty: api_secret.is_empty(),
+        }
+    }
+}
+
+impl Signer for HmacSigner {
+    fn sign(&self, payload: &str) -> Result<String> {
+        if self.empty {
+            return Err(AppError::MissingCredentials);
+        }
+        Ok(hex::encode(hmac::sign(&self.key, payload.as_bytes()).as_ref()))
+    }
+
+    fn sign_type(&self) -> Option<&'static str> {
+        // sign type 2 is HMAC-SHA256
+        Some("2")
+    }
+}
+
+///
+/// RSA-SHA256 with a self-generated private key, base64 encoded.
+/// The matching public key is registered with Bybit, which recognises RSA keys by their API key.
+///
+pub struct RsaSigner {
+    key: PKey<Private>,
+}
+
+impl RsaSigner {
+    ///
+    /// Loads a PEM encoded private key (PKCS#1 or PKCS#8).
+    ///
+    pub fn from_pem(pem: &[u8]) -> Result<Self> {
+        let key = PKey::private_key_from_pem(pem)?;
+        if key.rsa().is_err() {
+            return Err(AppError::InvalidInput("Private key is not an RSA key".to_owned()));
+        }
+        Ok(RsaSigner { key })
+    }
+
+    pub fn from_pem_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_pem(&std::fs::read(path)?)
+    }
+}
+
+impl Signer for RsaSigner {
+    fn sign(&self, payload: &str) -> Result<String> {
+        let mut signer = openssl::sign::Signer::new(MessageDigest::sha256(), &self.key)?;
+        signer.update(payload.as_bytes())?;
+        Ok(openssl::base64::encode_block(&signer.sign_to_vec()?))
+    }
+}
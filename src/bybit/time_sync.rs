@@ -0,0 +1,66 @@
+use std::{
+    sync::atomic::{AtomicI64, AtomicU64, Ordering},
+    time::Duration,
+};
+
+use crate::helpers::utils;
+
+use super::Result;
+
+///
+/// Estimated offset between the local clock and Bybit's.
+/// Signed REST requests and WebSocket auth read their timestamps from here, so a drifting
+/// local clock does not push them outside `recv_window`. `HttpManager::sync_time` takes a
+/// sample from `/v5/market/time`; until then the offset is zero.
+///
+#[derive(Debug, Default)]
+pub struct TimeSync {
+    offset_ms: AtomicI64,
+    latency_ms: AtomicU64,
+    synced_at: AtomicU64,
+}
+
+impl TimeSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Server time minus local time, in milliseconds.
+    ///
+    pub fn offset(&self) -> i64 {
+        self.offset_ms.load(Ordering::Relaxed)
+    }
+
+    /// Round-trip time of the last sample.
+    pub fn latency(&self) -> Duration {
+        Duration::from_millis(self.latency_ms.load(Ordering::Relaxed))
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.synced_at.load(Ordering::Relaxed) != 0
+    }
+
+    ///
+    /// Current server time estimate in milliseconds.
+    ///
+    pub fn timestamp(&self) -> Result<u128> {
+        let local = utils::generate_timestamp()? as i128;
+        Ok((local + self.offset() as i128).max(0) as u128)
+    }
+
+    ///
+    /// Records a sample: the request left at `sent_ms` and its reply arrived at
+    /// `received_ms` (both local), carrying `server_ms`. The server is assumed to have
+    /// stamped the reply halfway through the round trip. Returns the new offset.
+    ///
+    pub fn record(&self, sent_ms: u128, received_ms: u128, server_ms: u128) -> i64 {
+        let latency = received_ms.saturating_sub(sent_ms);
+        let midpoint = (sent_ms + latency / 2) as i128;
+        let offset = (server_ms as i128 - midpoint) as i64;
+        self.offset_ms.store(offset, Ordering::Relaxed);
+        self.latency_ms.store(latency as u64, Ordering::Relaxed);
+        self.synced_at.store(received_ms as u64, Ordering::Relaxed);
+        offset
+    }
+}
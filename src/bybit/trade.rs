@@ -1,4 +1,5 @@
 #![allow(unused)]
+#![allow(clippy::empty_line_after_outer_attr, clippy::empty_docs)]
 use async_trait::async_trait;
 use serde::Serialize;
 use std::{
@@ -7,31 +8,42 @@ use std::{
     sync::Arc,
 };
 
-use futures::Future;
+use chrono::{DateTime, Utc};
+use futures::{stream::BoxStream, Future};
 use reqwest::Method;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
-use crate::endpoints::v5trade;
+use crate::{
+    endpoints::v5trade,
+    models::order::{
+        AmendOrderRequest, BatchItem, BatchOrderResult, CancelOrderRequest, OrderAck,
+        PlaceOrderRequest,
+    },
+};
+
+pub use crate::models::order::BatchOrderRequest;
 
 use super::{
     http_manager::{HttpManager, Manager},
+    instruments::InstrumentRegistry,
+    pagination::{paginate, paginate_range, SEVEN_DAYS},
     Result,
 };
 
-/// Structure used for batch place & amend orders
-#[derive(serde_derive::Serialize)]
-pub struct BatchOrderRequest {
-    pub category: String,
-    pub request: Vec<HashMap<String, String>>,
-}
-
 #[async_trait]
 pub trait Trade {
     fn new(http_manager: Arc<HttpManager>) -> Self;
     async fn place_order(&self, query: HashMap<String, String>) -> Result<Value>;
-    async fn batch_place_order(&self, query: BatchOrderRequest) -> Result<Value>;
+    async fn batch_place_order(
+        &self,
+        request: BatchOrderRequest<PlaceOrderRequest>,
+    ) -> Result<Vec<BatchOrderResult>>;
     async fn amend_order(&self, query: HashMap<String, String>) -> Result<Value>;
-    async fn batch_amend_order(&self, query: BatchOrderRequest) -> Result<Value>;
+    async fn batch_amend_order(
+        &self,
+        request: BatchOrderRequest<AmendOrderRequest>,
+    ) -> Result<Vec<BatchOrderResult>>;
 
     async fn cancel_order(&self, query: HashMap<String, String>) -> Result<Value>;
 
@@ -41,16 +53,118 @@ pub trait Trade {
 
     async fn get_order_history(&self, query: HashMap<String, String>) -> Result<Value>;
 
+    async fn batch_cancel_order(
+        &self,
+        request: BatchOrderRequest<CancelOrderRequest>,
+    ) -> Result<Vec<BatchOrderResult>>;
+
+    #[deprecated(note = "use `batch_amend_order`")]
     async fn amend_batch_order(&self, query: HashMap<String, String>) -> Result<Value>;
 
+    #[deprecated(note = "use `batch_cancel_order`")]
     async fn cancel_batch_order(&self, query: HashMap<String, String>) -> Result<Value>;
+
     async fn get_borrow_quota(&self, query: HashMap<String, String>) -> Result<Value>;
 
     async fn set_dcp(&self, query: HashMap<String, String>) -> Result<Value>;
+
+    ///
+    /// Checks a typed order before it is sent.
+    ///
+    async fn validate_order(&self, request: &PlaceOrderRequest) -> Result<()> {
+        request.validate()
+    }
+
+    ///
+    /// Validates a typed order and places it. Numbers such as `positionIdx` and flags
+    /// such as `reduceOnly` are sent as JSON numbers and booleans, as Bybit expects.
+    ///
+    async fn place_order_typed(&self, request: &PlaceOrderRequest) -> Result<OrderAck>;
+
+    ///
+    /// Every open order from `get_open_orders`, across all of its pages.
+    ///
+    fn get_open_orders_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_open_orders(query))
+    }
+
+    ///
+    /// Every order from `get_order_history`, across all of its pages.
+    ///
+    fn get_order_history_stream<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate(query, move |query| self.get_order_history(query))
+    }
+
+    ///
+    /// Orders from `get_order_history` between `start` and `end`, requested in seven day
+    /// windows.
+    ///
+    fn get_order_history_range<'a, T>(
+        &'a self,
+        query: HashMap<String, String>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BoxStream<'a, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'a,
+        Self: Sync,
+    {
+        paginate_range(
+            query,
+            start,
+            end,
+            SEVEN_DAYS,
+            Some("orderId"),
+            move |query| self.get_order_history(query),
+        )
+    }
 }
 pub struct TradeHTTP {
     http_manager: Arc<HttpManager>,
+    instruments: Option<Arc<InstrumentRegistry>>,
 }
+
+impl TradeHTTP {
+    ///
+    /// Also checks typed orders against the instrument filters before sending them.
+    ///
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentRegistry>) -> Self {
+        self.instruments = Some(instruments);
+        self
+    }
+
+    ///
+    /// Posts a batch and joins `result.list` with the per-order status in `retExtInfo.list`.
+    ///
+    async fn submit_batch<T: BatchItem + Send>(
+        &self,
+        endpoint: v5trade::Trade,
+        request: BatchOrderRequest<T>,
+    ) -> Result<Vec<BatchOrderResult>> {
+        let response = self
+            .http_manager
+            .submit_post_request_raw(&endpoint.to_string(), request)
+            .await?;
+        let ret_ext_info = response.ret_ext_info.clone();
+        let result = response.into_result()?;
+        BatchOrderResult::from_reply(&result, &ret_ext_info)
+    }
+}
+
 #[async_trait]
 impl Trade for TradeHTTP {
     ///
@@ -59,7 +173,18 @@ impl Trade for TradeHTTP {
     ///
     ///
     fn new(http_manager: Arc<HttpManager>) -> Self {
-        TradeHTTP { http_manager }
+        TradeHTTP {
+            http_manager,
+            instruments: None,
+        }
+    }
+
+    async fn validate_order(&self, request: &PlaceOrderRequest) -> Result<()> {
+        request.validate()?;
+        match &self.instruments {
+            Some(instruments) => instruments.validate_order(request).await,
+            None => Ok(()),
+        }
     }
     ////
     /// This method supports to create the order for spot, spot margin, linear perpetual, inverse futures and options.
@@ -80,19 +205,34 @@ impl Trade for TradeHTTP {
             .await
     }
 
+    async fn place_order_typed(&self, request: &PlaceOrderRequest) -> Result<OrderAck> {
+        self.validate_order(request).await?;
+        let result = self
+            .http_manager
+            .submit_post_request(
+                Method::POST,
+                &v5trade::Trade::PlaceOrder.to_string(),
+                true,
+                request,
+            )
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
     ////
     /// This method supports to create a batch of orders for spot, spot margin, linear perpetual, inverse futures and options.
     /// Required args:
     ///     category (string): Product type Unified account: spot, linear, optionNormal account: linear, inverse. Please note that category is not involved with business logic
     ///     request (array): a list of orders as in the place_order API
     /// Returns:
-    ///     Request results as JSON data.
+    ///     One result per order, in request order.
     /// Additional information:
     ///     https://bybit-exchange.github.io/docs/v5/order/batch-place
-    async fn batch_place_order(&self, query: BatchOrderRequest) -> Result<Value> {
-        let endpoint = v5trade::Trade::BatchPlaceOrder.to_string();
-        self.http_manager
-            .submit_post_request(Method::POST, &endpoint, true, query)
+    async fn batch_place_order(
+        &self,
+        request: BatchOrderRequest<PlaceOrderRequest>,
+    ) -> Result<Vec<BatchOrderResult>> {
+        self.submit_batch(v5trade::Trade::BatchPlaceOrder, request)
             .await
     }
 
@@ -124,17 +264,14 @@ impl Trade for TradeHTTP {
     ///     category (string): Product type Unified account: spot, linear, optionNormal account: linear, inverse. Please note that category is not involved with business logic
     ///     request (array): list of order amend parameters. See amend_order.
     /// Returns:
-    ///     Request results as JSON data.
+    ///     One result per order, in request order.
     /// Additional information:
     ///     https://bybit-exchange.github.io/docs/v5/order/batch-amend
-    async fn batch_amend_order(&self, query: BatchOrderRequest) -> Result<Value> {
-        self.http_manager
-            .submit_post_request(
-                Method::POST,
-                &v5trade::Trade::BatchAmendOrder.to_string(),
-                true,
-                query,
-            )
+    async fn batch_amend_order(
+        &self,
+        request: BatchOrderRequest<AmendOrderRequest>,
+    ) -> Result<Vec<BatchOrderResult>> {
+        self.submit_batch(v5trade::Trade::BatchAmendOrder, request)
             .await
     }
 
@@ -234,21 +371,32 @@ impl Trade for TradeHTTP {
             )
             .await
     }
-    /// Covers: Option (Unified Account)
+    /// This endpoint allows you to cancel more than one open order in a single request.
 
     ///     Required args:
-    ///         category (string): Product type. option
+    ///         category (string): Product type. spot, linear, inverse, option
     ///         request (array): Object
     ///         > symbol (string): Symbol name
-    ///         > side (string): Buy, Sell
-    ///         > orderType (string): Market, Limit
-    ///         > qty (string): Order quantity
+    ///         > orderId (string): Order ID. Either orderId or orderLinkId is required
 
     ///     Returns:
-    ///         Request results as JSON data.
+    ///         One result per order, in request order.
 
     ///     Additional information:
-    ///         https://bybit-exchange.github.io/docs/v5/order/batch-place
+    ///         https://bybit-exchange.github.io/docs/v5/order/batch-cancel
+    async fn batch_cancel_order(
+        &self,
+        request: BatchOrderRequest<CancelOrderRequest>,
+    ) -> Result<Vec<BatchOrderResult>> {
+        self.submit_batch(v5trade::Trade::BatchCancelOrder, request)
+            .await
+    }
+
+    /// Posts `query` unchanged to the batch amend endpoint. Prefer `batch_amend_order`,
+    /// which also reports the outcome of each order.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/order/batch-amend
     async fn amend_batch_order(&self, query: HashMap<String, String>) -> Result<Value> {
         self.http_manager
             .submit_post_request(
@@ -259,23 +407,17 @@ impl Trade for TradeHTTP {
             )
             .await
     }
-    /// This endpoint allows you to cancel more than one open order in a single request.
 
-    ///     Required args:
-    ///         category (string): Product type. option
-    ///         request (array): Object
-    ///         > symbol (string): Symbol name
-
-    ///     Returns:
-    ///         Request results as JSON data.
-
-    ///     Additional information:
-    ///         https://bybit-exchange.github.io/docs/v5/order/batch-cancel
+    /// Posts `query` unchanged to the batch cancel endpoint. Prefer `batch_cancel_order`,
+    /// which also reports the outcome of each order.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/order/batch-cancel
     async fn cancel_batch_order(&self, query: HashMap<String, String>) -> Result<Value> {
         self.http_manager
             .submit_post_request(
                 Method::POST,
-                &v5trade::Trade::CancelAllOrders.to_string(),
+                &v5trade::Trade::BatchCancelOrder.to_string(),
                 true,
                 query,
             )
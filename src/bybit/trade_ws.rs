@@ -0,0 +1,454 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt, StreamExt,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::time::Instant;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use crate::{
+    endpoints::v5websocket,
+    helpers::utils,
+    models::order::{
+        AmendOrderRequest, BatchItem, BatchOrderRequest, BatchOrderResult, CancelOrderRequest,
+        PlaceOrderRequest,
+    },
+};
+
+use super::{
+    http_manager::HttpManager,
+    signer::{HmacSigner, Signer},
+    time_sync::TimeSync,
+    trade::{Trade, TradeHTTP},
+    websocket_stream::{self, Heartbeat, Socket},
+    AppError, Result,
+};
+
+type Pending = Arc<Mutex<HashMap<String, oneshot::Sender<Result<Value>>>>>;
+
+///
+/// Operations accepted by the `/v5/trade` stream.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeOp {
+    Create,
+    Amend,
+    Cancel,
+    CreateBatch,
+    AmendBatch,
+    CancelBatch,
+}
+
+impl std::fmt::Display for TradeOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TradeOp::Create => write!(f, "order.create"),
+            TradeOp::Amend => write!(f, "order.amend"),
+            TradeOp::Cancel => write!(f, "order.cancel"),
+            TradeOp::CreateBatch => write!(f, "order.create-batch"),
+            TradeOp::AmendBatch => write!(f, "order.amend-batch"),
+            TradeOp::CancelBatch => write!(f, "order.cancel-batch"),
+        }
+    }
+}
+
+struct Connection {
+    outgoing: mpsc::UnboundedSender<Message>,
+    pending: Pending,
+}
+
+///
+/// Order entry over the `/v5/trade` WebSocket.
+/// Each request carries a `reqId` and is resolved by the reply with the same id.
+///
+pub struct TradeWs {
+    api_key: String,
+    signer: Arc<dyn Signer>,
+    url: String,
+    recv_window: u64,
+    timeout: Duration,
+    fallback: Option<Arc<HttpManager>>,
+    time_sync: Arc<TimeSync>,
+    heartbeat: Heartbeat,
+    connection: Mutex<Option<Connection>>,
+    next_id: AtomicU64,
+}
+
+impl TradeWs {
+    ///
+    /// Initializes a trade stream client on mainnet or testnet.
+    ///
+    pub fn new(api_key: String, api_secret: String, testnet: bool) -> Self {
+        let url = websocket_stream::stream_url(&v5websocket::WebSocket::Trade.to_string(), testnet);
+        Self::from_url(api_key, api_secret, url)
+    }
+
+    ///
+    /// Initializes a client against an explicit url, e.g. a local server.
+    ///
+    pub fn from_url(api_key: String, api_secret: String, url: String) -> Self {
+        TradeWs {
+            api_key,
+            signer: Arc::new(HmacSigner::new(&api_secret)),
+            url,
+            recv_window: 5000,
+            timeout: Duration::from_secs(10),
+            fallback: None,
+            time_sync: Arc::new(TimeSync::new()),
+            heartbeat: Heartbeat::default(),
+            connection: Mutex::new(None),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    ///
+    /// Default time to wait for a reply before a request fails.
+    ///
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    ///
+    /// Sends requests through `TradeHTTP` while the socket is down.
+    /// The stream also adopts the manager's clock offset.
+    ///
+    pub fn with_fallback(mut self, http_manager: Arc<HttpManager>) -> Self {
+        self.time_sync = http_manager.time_sync();
+        self.fallback = Some(http_manager);
+        self
+    }
+
+    ///
+    /// Clock offset for auth `expires` and the `X-BAPI-TIMESTAMP` header.
+    ///
+    pub fn with_time_sync(mut self, time_sync: Arc<TimeSync>) -> Self {
+        self.time_sync = time_sync;
+        self
+    }
+
+    ///
+    /// Ping interval and stale timeout of the socket. A stale socket is dropped,
+    /// so pending requests fail and later ones use the fallback.
+    ///
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    ///
+    /// Signs the auth op with `signer` instead of HMAC with the secret, e.g. for RSA keys.
+    ///
+    pub fn with_signer(mut self, signer: Arc<dyn Signer>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    pub fn is_connected(&self) -> bool {
+        match self.connection.lock().unwrap().as_ref() {
+            Some(connection) => !connection.outgoing.is_closed(),
+            None => false,
+        }
+    }
+
+    ///
+    /// Opens and authenticates the socket. Calling it again replaces a dropped connection.
+    ///
+    pub async fn connect(&self) -> Result<()> {
+        if self.api_key.is_empty() {
+            return Err(AppError::MissingCredentials);
+        }
+        let (mut socket, _) = connect_async(self.url.as_str()).await?;
+        websocket_stream::authenticate(
+            &mut socket,
+            &self.api_key,
+            self.signer.as_ref(),
+            &self.time_sync,
+        )
+        .await?;
+
+        let (outgoing, outgoing_rx) = mpsc::unbounded();
+        let pending: Pending = Arc::new(Mutex::new(HashMap::new()));
+        tokio::spawn(run_connection(
+            socket,
+            outgoing_rx,
+            pending.clone(),
+            self.heartbeat.clone(),
+        ));
+
+        let previous = self
+            .connection
+            .lock()
+            .unwrap()
+            .replace(Connection { outgoing, pending });
+        if let Some(previous) = previous {
+            previous.outgoing.close_channel();
+        }
+        Ok(())
+    }
+
+    ///
+    /// Closes the socket. Requests still waiting for a reply fail.
+    ///
+    pub fn close(&self) {
+        if let Some(connection) = self.connection.lock().unwrap().take() {
+            connection.outgoing.close_channel();
+        }
+    }
+
+    ///
+    /// Sends `op` with the given args and waits up to `timeout` for the matching reply.
+    /// Returns the full reply, including `retCode`, `retMsg` and `data`, whatever its `retCode`.
+    ///
+    pub async fn request<T: Serialize>(
+        &self,
+        op: TradeOp,
+        args: T,
+        timeout: Duration,
+    ) -> Result<Value> {
+        let req_id = format!(
+            "{}-{}",
+            utils::generate_timestamp()?,
+            self.next_id.fetch_add(1, Ordering::Relaxed)
+        );
+        let message = json!({
+            "reqId": req_id,
+            "header": {
+                "X-BAPI-TIMESTAMP": self.time_sync.timestamp()?.to_string(),
+                "X-BAPI-RECV-WINDOW": self.recv_window.to_string(),
+            },
+            "op": op.to_string(),
+            "args": [args],
+        });
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let pending = {
+            let connection = self.connection.lock().unwrap();
+            let connection = connection.as_ref().ok_or_else(not_connected)?;
+            connection
+                .pending
+                .lock()
+                .unwrap()
+                .insert(req_id.clone(), reply_tx);
+            connection
+                .outgoing
+                .unbounded_send(Message::Text(message.to_string()))
+                .map_err(|_| not_connected())?;
+            connection.pending.clone()
+        };
+
+        match tokio::time::timeout(timeout, reply_rx).await {
+            Ok(Ok(reply)) => reply,
+            Ok(Err(_)) => Err(AppError::ConnectionClosed(
+                "Trade WebSocket closed before the reply arrived".to_owned(),
+            )),
+            Err(_) => {
+                pending.lock().unwrap().remove(&req_id);
+                Err(AppError::Timeout(format!(
+                    "{} {} timed out after {:?}",
+                    op, req_id, timeout
+                )))
+            }
+        }
+    }
+
+    ///
+    /// Place an order. Same arguments as `Trade::place_order`.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/websocket/trade/guideline
+    pub async fn place_order(&self, query: HashMap<String, String>) -> Result<Value> {
+        if let Some(http) = self.fallback_client() {
+            return http.place_order(query).await;
+        }
+        self.call(TradeOp::Create, query).await
+    }
+
+    ///
+    /// Place a batch of orders. Same arguments as `Trade::batch_place_order`.
+    ///
+    pub async fn batch_place_order(
+        &self,
+        request: BatchOrderRequest<PlaceOrderRequest>,
+    ) -> Result<Vec<BatchOrderResult>> {
+        if let Some(http) = self.fallback_client() {
+            return http.batch_place_order(request).await;
+        }
+        self.call_batch(TradeOp::CreateBatch, request).await
+    }
+
+    ///
+    /// Amend an open order. Same arguments as `Trade::amend_order`.
+    ///
+    pub async fn amend_order(&self, query: HashMap<String, String>) -> Result<Value> {
+        if let Some(http) = self.fallback_client() {
+            return http.amend_order(query).await;
+        }
+        self.call(TradeOp::Amend, query).await
+    }
+
+    ///
+    /// Amend a batch of open orders. Same arguments as `Trade::batch_amend_order`.
+    ///
+    pub async fn batch_amend_order(
+        &self,
+        request: BatchOrderRequest<AmendOrderRequest>,
+    ) -> Result<Vec<BatchOrderResult>> {
+        if let Some(http) = self.fallback_client() {
+            return http.batch_amend_order(request).await;
+        }
+        self.call_batch(TradeOp::AmendBatch, request).await
+    }
+
+    ///
+    /// Cancel an open order. Same arguments as `Trade::cancel_order`.
+    ///
+    pub async fn cancel_order(&self, query: HashMap<String, String>) -> Result<Value> {
+        if let Some(http) = self.fallback_client() {
+            return http.cancel_order(query).await;
+        }
+        self.call(TradeOp::Cancel, query).await
+    }
+
+    ///
+    /// Cancel a batch of open orders. Same arguments as `Trade::batch_cancel_order`.
+    ///
+    pub async fn batch_cancel_order(
+        &self,
+        request: BatchOrderRequest<CancelOrderRequest>,
+    ) -> Result<Vec<BatchOrderResult>> {
+        if let Some(http) = self.fallback_client() {
+            return http.batch_cancel_order(request).await;
+        }
+        self.call_batch(TradeOp::CancelBatch, request).await
+    }
+
+    ///
+    /// Sends `op` with the default timeout and returns the reply's `data`.
+    /// A non-zero `retCode` is returned as an error, as with the REST clients.
+    ///
+    async fn call<T: Serialize>(&self, op: TradeOp, args: T) -> Result<Value> {
+        Ok(self.call_reply(op, args).await?["data"].take())
+    }
+
+    ///
+    /// `call` for the batch ops, joining `data.list` with the statuses in `retExtInfo.list`.
+    ///
+    async fn call_batch<T: BatchItem>(
+        &self,
+        op: TradeOp,
+        request: BatchOrderRequest<T>,
+    ) -> Result<Vec<BatchOrderResult>> {
+        let reply = self.call_reply(op, request).await?;
+        BatchOrderResult::from_reply(&reply["data"], &reply["retExtInfo"])
+    }
+
+    async fn call_reply<T: Serialize>(&self, op: TradeOp, args: T) -> Result<Value> {
+        let reply = self.request(op, args, self.timeout).await?;
+        let ret_code = reply["retCode"].as_i64().unwrap_or_default();
+        if ret_code != 0 {
+            return Err(AppError::from_ret_code(
+                ret_code,
+                reply["retMsg"].as_str().unwrap_or_default().to_owned(),
+                reply["retExtInfo"].clone(),
+            ));
+        }
+        Ok(reply)
+    }
+
+    fn fallback_client(&self) -> Option<TradeHTTP> {
+        if self.is_connected() {
+            return None;
+        }
+        self.fallback
+            .as_ref()
+            .map(|http_manager| TradeHTTP::new(http_manager.clone()))
+    }
+}
+
+///
+/// Pumps requests and replies until the socket drops, goes stale or the client closes it.
+///
+async fn run_connection(
+    mut socket: Socket,
+    mut outgoing: mpsc::UnboundedReceiver<Message>,
+    pending: Pending,
+    heartbeat: Heartbeat,
+) {
+    let mut ping =
+        tokio::time::interval_at(Instant::now() + heartbeat.interval, heartbeat.interval);
+    ping.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut ping_id = 0u64;
+    let stale = tokio::time::sleep(heartbeat.stale_after);
+    tokio::pin!(stale);
+
+    loop {
+        tokio::select! {
+            _ = ping.tick() => {
+                ping_id += 1;
+                if socket.send(websocket_stream::ping_message(ping_id)).await.is_err() {
+                    break;
+                }
+            }
+            _ = &mut stale => {
+                let _ = socket.close(None).await;
+                break;
+            }
+            message = outgoing.next() => match message {
+                Some(message) => {
+                    if socket.send(message).await.is_err() {
+                        break;
+                    }
+                }
+                None => {
+                    let _ = socket.close(None).await;
+                    break;
+                }
+            },
+            incoming = socket.next() => {
+                stale.as_mut().reset(Instant::now() + heartbeat.stale_after);
+                match incoming {
+                    Some(Ok(Message::Text(text))) if websocket_stream::is_pong(&text) => {}
+                    Some(Ok(Message::Text(text))) => dispatch(&text, &pending),
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+
+    outgoing.close();
+    for (_, reply) in pending.lock().unwrap().drain() {
+        let _ = reply.send(Err(AppError::ConnectionClosed(
+            "Trade WebSocket connection closed".to_owned(),
+        )));
+    }
+}
+
+fn not_connected() -> AppError {
+    AppError::ConnectionClosed("Trade WebSocket is not connected".to_owned())
+}
+
+fn dispatch(text: &str, pending: &Pending) {
+    let reply: Value = match serde_json::from_str(text) {
+        Ok(reply) => reply,
+        Err(_) => return,
+    };
+    let req_id = match reply.get("reqId").and_then(Value::as_str) {
+        Some(req_id) => req_id.to_owned(),
+        None => return,
+    };
+    if let Some(sender) = pending.lock().unwrap().remove(&req_id) {
+        let _ = sender.send(Ok(reply));
+    }
+}
@@ -1,4 +1,5 @@
 #![allow(unused)]
+#![allow(clippy::empty_line_after_outer_attr, clippy::empty_docs)]
 use async_trait::async_trait;
 use std::{
     collections::{BTreeMap, HashMap},
@@ -10,7 +11,14 @@ use futures::Future;
 use reqwest::Method;
 use serde_json::Value;
 
-use crate::endpoints::v5user;
+use crate::{
+    endpoints::v5user,
+    models::user::{
+        AffiliateCustomerInfo, ApiKeyInfo, CreateSubApiKeyRequest, CreateSubUidRequest,
+        DeleteSubUidRequest, FreezeSubUidRequest, MemberWalletTypes, SubApiKeys, SubMember,
+        SubMembers,
+    },
+};
 
 use super::{
     Result,
@@ -21,12 +29,12 @@ pub trait User {
     fn new(http_manager: Arc<HttpManager>) -> Self;
     async fn create_sub_uid(
         &self,
-        query: HashMap<String, String>,
+        request: &CreateSubUidRequest,
     ) -> Result<Value>;
 
     async fn create_sub_api_key(
         &self,
-        query: HashMap<String, String>,
+        request: &CreateSubApiKeyRequest,
     ) -> Result<Value>;
 
     async fn get_sub_uid_list(
@@ -35,7 +43,7 @@ pub trait User {
     ) -> Result<Value>;
     async fn freeze_sub_uid(
         &self,
-        query: HashMap<String, String>,
+        request: &FreezeSubUidRequest,
     ) -> Result<Value>;
 
     async fn get_api_key_information(
@@ -62,6 +70,78 @@ pub trait User {
         &self,
         query: HashMap<String, String>,
     ) -> Result<Value>;
+
+    async fn get_sub_api_keys(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    async fn delete_sub_uid(
+        &self,
+        request: &DeleteSubUidRequest,
+    ) -> Result<Value>;
+
+    async fn get_uid_wallet_type(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    async fn get_affiliate_customer_info(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value>;
+
+    // Typed versions of the methods above.
+
+    async fn create_sub_uid_typed(&self, request: &CreateSubUidRequest) -> Result<SubMember> {
+        Ok(serde_json::from_value(self.create_sub_uid(request).await?)?)
+    }
+
+    ///
+    /// The new key, including its `secret`, which cannot be read again later.
+    ///
+    async fn create_sub_api_key_typed(
+        &self,
+        request: &CreateSubApiKeyRequest,
+    ) -> Result<ApiKeyInfo> {
+        Ok(serde_json::from_value(self.create_sub_api_key(request).await?)?)
+    }
+
+    async fn get_sub_uid_list_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Vec<SubMember>> {
+        let members: SubMembers = serde_json::from_value(self.get_sub_uid_list(query).await?)?;
+        Ok(members.sub_members)
+    }
+
+    async fn get_api_key_information_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<ApiKeyInfo> {
+        Ok(serde_json::from_value(self.get_api_key_information(query).await?)?)
+    }
+
+    async fn get_sub_api_keys_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<SubApiKeys> {
+        Ok(serde_json::from_value(self.get_sub_api_keys(query).await?)?)
+    }
+
+    async fn get_uid_wallet_type_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<MemberWalletTypes> {
+        Ok(serde_json::from_value(self.get_uid_wallet_type(query).await?)?)
+    }
+
+    async fn get_affiliate_customer_info_typed(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<AffiliateCustomerInfo> {
+        Ok(serde_json::from_value(self.get_affiliate_customer_info(query).await?)?)
+    }
 }
 
 pub struct UserHTTP {
@@ -80,51 +160,58 @@ impl User for UserHTTP {
     }
 
     /// Create a new sub user id. Use master user's api key only.
-
-    ///     Required args:
-    ///         username (string): Give a username of the new sub user id. 6-16 characters, must include both numbers and letters.cannot be the same as the exist or deleted one.
-    ///         memberType (integer): 1: normal sub account, 6: custodial sub account
-
-    ///     Returns:
-    ///         Request results as HashMap.
-
-    ///     Additional information:
-    ///         https://bybit-exchange.github.io/docs/v5/user/create-subuid
+    ///
+    /// Request fields (`CreateSubUidRequest::new(username, member_type)`):
+    ///     username: 6-16 characters, must include both numbers and letters. Cannot be the same as an existing or deleted one
+    ///     member_type: 1 normal sub account, 6 custodial sub account
+    ///
+    /// Optional fields, set with the builder methods:
+    ///     password, quick_login (switch), is_uta, note
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/user/create-subuid
     async fn create_sub_uid(
         &self,
-        query: HashMap<String, String>,
+        request: &CreateSubUidRequest,
     ) -> Result<Value> {
         let endpoint = v5user::User::CreateSubUid.to_string();
         let result = self
             .http_manager
-            .submit_request(Method::GET, &endpoint, query, true)
+            .submit_post_request(Method::POST, &endpoint, true, request)
             .await?;
         Ok(result)
     }
 
     /// To create new API key for those newly created sub UID. Use master user's api key only.
-
-    ///     Required args:
-    ///         subuid (integer): Sub user Id
-    ///         readOnly (integer): 0: Read and Write. 1: Read only
-    ///         permissions (Object): Tick the types of permission. one of below types must be passed, otherwise the error is thrown
-
-    ///     Returns:
-    ///         Request results as HashMap.
-
-    ///     Additional information:
-    ///         https://bybit-exchange.github.io/docs/v5/user/create-subuid-apikey
+    ///
+    /// Request fields (`CreateSubApiKeyRequest::new(subuid, read_only, permissions)`):
+    ///     subuid: Sub user Id
+    ///     read_only: false for read and write, true for read only
+    ///     permissions: `ApiPermissions` with at least one permission group set, otherwise the request is rejected
+    ///
+    /// Optional fields, set with the builder methods:
+    ///     note, ips
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/user/create-subuid-apikey
     async fn create_sub_api_key(
         &self,
-        query: HashMap<String, String>,
+        request: &CreateSubApiKeyRequest,
     ) -> Result<Value> {
         let endpoint = v5user::User::CreateSubApiKey.to_string();
         let result = self
             .http_manager
-            .submit_request(Method::GET, &endpoint, query, true)
+            .submit_post_request(Method::POST, &endpoint, true, request)
             .await?;
         Ok(result)
     }
+
     /// Get all sub uid of master account. Use master user's api key only.
 
     ///     Returns:
@@ -145,24 +232,24 @@ impl User for UserHTTP {
     }
 
     /// Froze sub uid. Use master user's api key only.
-
-    ///     Required args:
-    ///         subuid (integer): Sub user Id
-    ///         frozen (integer): 0: unfreeze, 1: freeze
-
-    ///     Returns:
-    ///         Request results as HashMap.
-
-    ///     Additional information:
-    ///         https://bybit-exchange.github.io/docs/v5/user/froze-subuid
+    ///
+    /// Request fields (`FreezeSubUidRequest::new(subuid, frozen)`):
+    ///     subuid: Sub user Id
+    ///     frozen: true freezes, false unfreezes
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/user/froze-subuid
     async fn freeze_sub_uid(
         &self,
-        query: HashMap<String, String>,
+        request: &FreezeSubUidRequest,
     ) -> Result<Value> {
         let endpoint = v5user::User::FreezeSubUid.to_string();
         let result = self
             .http_manager
-            .submit_request(Method::GET, &endpoint, query, true)
+            .submit_post_request(Method::POST, &endpoint, true, request)
             .await?;
         Ok(result)
     }
@@ -268,4 +355,92 @@ impl User for UserHTTP {
             .await?;
         Ok(result)
     }
+
+    /// Get all API keys of a sub account. Use master user's api key only.
+    ///
+    /// Required args:
+    ///     subMemberId (string): Sub user Id
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/user/list-sub-apikeys
+    async fn get_sub_api_keys(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        let endpoint = v5user::User::GetSubApiKeys.to_string();
+        let result = self
+            .http_manager
+            .submit_request(Method::GET, &endpoint, query, true)
+            .await?;
+        Ok(result)
+    }
+
+    /// Delete a sub UID. The sub account must hold no assets. Use master user's api key only.
+    ///
+    /// Required args:
+    ///     subMemberId (string): Sub user Id
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/user/rm-subuid
+    async fn delete_sub_uid(
+        &self,
+        request: &DeleteSubUidRequest,
+    ) -> Result<Value> {
+        let endpoint = v5user::User::DeleteSubUid.to_string();
+        let result = self
+            .http_manager
+            .submit_post_request(Method::POST, &endpoint, true, request)
+            .await?;
+        Ok(result)
+    }
+
+    /// Query the wallet types (member type) of the master UID or of its sub UIDs.
+    ///
+    /// Optional args:
+    ///     memberIds (string): Comma separated sub UIDs. The master UID when not passed
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/user/wallet-type
+    async fn get_uid_wallet_type(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        let endpoint = v5user::User::GetUidWalletType.to_string();
+        let result = self
+            .http_manager
+            .submit_request(Method::GET, &endpoint, query, true)
+            .await?;
+        Ok(result)
+    }
+
+    /// Get the trading volumes and deposits of a customer invited by the affiliate. Use the affiliate's api key only.
+    ///
+    /// Required args:
+    ///     uid (string): The customer's UID
+    ///
+    /// Returns:
+    ///     Request results as HashMap.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/user/affiliate-info
+    async fn get_affiliate_customer_info(
+        &self,
+        query: HashMap<String, String>,
+    ) -> Result<Value> {
+        let endpoint = v5user::User::GetAffiliateCustomerInfo.to_string();
+        let result = self
+            .http_manager
+            .submit_request(Method::GET, &endpoint, query, true)
+            .await?;
+        Ok(result)
+    }
 }
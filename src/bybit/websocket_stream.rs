@@ -0,0 +1,923 @@
+use std::{
+    collections::BTreeSet,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::{net::TcpStream, time::Instant};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream};
+
+use crate::{endpoints::v5websocket, helpers::utils};
+
+use super::{
+    signer::{HmacSigner, Signer},
+    time_sync::TimeSync,
+    AppError, Error, Result,
+};
+
+pub(crate) type Socket = tokio_tungstenite::WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Bybit accepts at most this many topics in a single `subscribe` request.
+const MAX_TOPICS_PER_REQUEST: usize = 10;
+
+/// How long the signature sent with the `auth` op stays valid.
+const AUTH_EXPIRE_MS: u128 = 10_000;
+
+///
+/// Backoff applied between reconnect attempts after a stream drops.
+/// Each delay doubles up to `max_backoff` and is jittered by up to half its length.
+///
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Give up after this many failed attempts in a row. `None` retries forever.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    ///
+    /// Never reconnect; the event stream ends with the disconnect error.
+    ///
+    pub fn disabled() -> Self {
+        ReconnectPolicy {
+            max_attempts: Some(0),
+            ..Default::default()
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let base = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+        let half = base / 2;
+        half + half.mul_f64(rand::random::<f64>())
+    }
+}
+
+///
+/// Keep-alive settings. Bybit drops sockets that do not ping regularly.
+///
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    /// How often `{"op":"ping"}` is sent.
+    pub interval: Duration,
+    /// Treat the socket as stale and reconnect when nothing, pongs included,
+    /// arrives for this long.
+    pub stale_after: Duration,
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Heartbeat {
+            interval: Duration::from_secs(20),
+            stale_after: Duration::from_secs(60),
+        }
+    }
+}
+
+///
+/// Point-in-time view of a connection's health, for exporting to monitoring.
+///
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthSnapshot {
+    pub connected: bool,
+    pub stale: bool,
+    /// Round trip of the last answered ping.
+    pub latency_ms: Option<u64>,
+    pub last_message_age_ms: Option<u64>,
+    pub reconnects: u64,
+}
+
+#[derive(Default)]
+struct HealthState {
+    connected: AtomicBool,
+    stale: AtomicBool,
+    /// Microseconds, `0` until the first pong.
+    latency: AtomicU64,
+    /// Epoch milliseconds, `0` until the first message.
+    last_message: AtomicU64,
+    reconnects: AtomicU64,
+}
+
+///
+/// Shared health of a connection, updated by its task.
+///
+#[derive(Clone, Default)]
+pub struct StreamHealth {
+    state: Arc<HealthState>,
+}
+
+impl StreamHealth {
+    pub fn is_connected(&self) -> bool {
+        self.state.connected.load(Ordering::Relaxed)
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.state.stale.load(Ordering::Relaxed)
+    }
+
+    pub fn latency(&self) -> Option<Duration> {
+        match self.state.latency.load(Ordering::Relaxed) {
+            0 => None,
+            micros => Some(Duration::from_micros(micros)),
+        }
+    }
+
+    pub fn last_message_age(&self) -> Option<Duration> {
+        match self.state.last_message.load(Ordering::Relaxed) {
+            0 => None,
+            at => Some(Duration::from_millis(now_ms().saturating_sub(at))),
+        }
+    }
+
+    pub fn reconnects(&self) -> u64 {
+        self.state.reconnects.load(Ordering::Relaxed)
+    }
+
+    pub fn snapshot(&self) -> HealthSnapshot {
+        HealthSnapshot {
+            connected: self.is_connected(),
+            stale: self.is_stale(),
+            latency_ms: self.latency().map(|latency| latency.as_millis() as u64),
+            last_message_age_ms: self.last_message_age().map(|age| age.as_millis() as u64),
+            reconnects: self.reconnects(),
+        }
+    }
+
+    fn set_connected(&self, connected: bool) {
+        self.state.connected.store(connected, Ordering::Relaxed);
+        if connected {
+            self.state.stale.store(false, Ordering::Relaxed);
+        }
+    }
+
+    fn record_message(&self) {
+        self.state.last_message.store(now_ms(), Ordering::Relaxed);
+    }
+
+    fn record_latency(&self, latency: Duration) {
+        let micros = (latency.as_micros() as u64).max(1);
+        self.state.latency.store(micros, Ordering::Relaxed);
+    }
+}
+
+fn now_ms() -> u64 {
+    utils::generate_timestamp().unwrap_or_default() as u64
+}
+
+///
+/// Product channel of the public stream.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicChannel {
+    Spot,
+    Linear,
+    Inverse,
+    Option,
+}
+
+impl PublicChannel {
+    fn endpoint(&self) -> v5websocket::WebSocket {
+        match self {
+            PublicChannel::Spot => v5websocket::WebSocket::PublicSpot,
+            PublicChannel::Linear => v5websocket::WebSocket::PublicLinear,
+            PublicChannel::Inverse => v5websocket::WebSocket::PublicInverse,
+            PublicChannel::Option => v5websocket::WebSocket::PublicOption,
+        }
+    }
+}
+
+///
+/// Builds the stream url for a path, picking mainnet or testnet the same way
+/// `HttpManager::new` does.
+///
+pub fn stream_url(path: &str, testnet: bool) -> String {
+    let sub_domain = if testnet { "stream-testnet" } else { "stream" };
+    format!("wss://{}.{}.com{}", sub_domain, "bybit", path)
+}
+
+/// Helpers for building public topic names.
+pub mod topic {
+    /// `orderbook.{depth}.{symbol}`
+    pub fn orderbook(depth: u32, symbol: &str) -> String {
+        format!("orderbook.{}.{}", depth, symbol)
+    }
+
+    /// `publicTrade.{symbol}`
+    pub fn public_trade(symbol: &str) -> String {
+        format!("publicTrade.{}", symbol)
+    }
+
+    /// `tickers.{symbol}`
+    pub fn tickers(symbol: &str) -> String {
+        format!("tickers.{}", symbol)
+    }
+
+    /// `kline.{interval}.{symbol}`
+    pub fn kline(interval: &str, symbol: &str) -> String {
+        format!("kline.{}.{}", interval, symbol)
+    }
+}
+
+///
+/// Reply to an `op` request such as `subscribe`, `unsubscribe` or `ping`.
+///
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpResponse {
+    #[serde(default)]
+    pub success: bool,
+    #[serde(default)]
+    pub ret_msg: String,
+    #[serde(default)]
+    pub conn_id: String,
+    pub req_id: Option<String>,
+    pub op: Option<String>,
+    /// Option channels report the accepted and rejected topics here.
+    pub data: Option<Value>,
+}
+
+///
+/// Data pushed for a subscribed topic.
+///
+#[derive(Debug, Clone, Deserialize)]
+pub struct TopicMessage {
+    pub topic: String,
+    /// `snapshot` or `delta`, when the topic distinguishes them.
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub ts: Option<u64>,
+    pub cts: Option<u64>,
+    pub data: Value,
+}
+
+/// Event type produced by a connection task.
+trait Event: Sized + Send + 'static {
+    fn parse(text: &str) -> Result<Self>;
+    fn reconnected() -> Self;
+}
+
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    Response(OpResponse),
+    Message(TopicMessage),
+    /// The socket dropped and was re-established with every topic resubscribed.
+    /// Local state built from snapshots should be resynced.
+    Reconnected,
+}
+
+impl Event for StreamEvent {
+    fn reconnected() -> Self {
+        StreamEvent::Reconnected
+    }
+
+    fn parse(text: &str) -> Result<StreamEvent> {
+        let value: Value = serde_json::from_str(text)?;
+        if value.get("topic").is_some() {
+            Ok(StreamEvent::Message(serde_json::from_value(value)?))
+        } else {
+            Ok(StreamEvent::Response(serde_json::from_value(value)?))
+        }
+    }
+}
+
+enum Command {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+    Close,
+}
+
+/// Events produced by a connection, in the order they were received.
+pub type EventStream<E = StreamEvent> = mpsc::UnboundedReceiver<Result<E>>;
+
+///
+/// Handle used to change the subscriptions of a running connection.
+///
+#[derive(Clone)]
+pub struct StreamHandle {
+    commands: mpsc::UnboundedSender<Command>,
+    health: StreamHealth,
+}
+
+impl StreamHandle {
+    ///
+    /// Connection state, ping latency and data freshness.
+    ///
+    pub fn health(&self) -> StreamHealth {
+        self.health.clone()
+    }
+
+    pub fn subscribe(&self, topics: Vec<String>) -> Result<()> {
+        self.send(Command::Subscribe(topics))
+    }
+
+    pub fn unsubscribe(&self, topics: Vec<String>) -> Result<()> {
+        self.send(Command::Unsubscribe(topics))
+    }
+
+    ///
+    /// Closes the socket and stops reconnecting. The event stream ends once the close completes.
+    ///
+    pub fn close(&self) {
+        let _ = self.commands.unbounded_send(Command::Close);
+    }
+
+    fn send(&self, command: Command) -> Result<()> {
+        self.commands
+            .unbounded_send(command)
+            .map_err(|_| AppError::ConnectionClosed("WebSocket connection is closed".to_owned()))
+    }
+}
+
+///
+/// Client for the `/v5/public/{spot,linear,inverse,option}` streams.
+///
+pub struct PublicStream {
+    url: String,
+    reconnect: ReconnectPolicy,
+    heartbeat: Heartbeat,
+}
+
+impl PublicStream {
+    ///
+    /// Initializes a client for the given channel on mainnet or testnet.
+    ///
+    pub fn new(channel: PublicChannel, testnet: bool) -> Self {
+        Self::from_url(stream_url(&channel.endpoint().to_string(), testnet))
+    }
+
+    ///
+    /// Initializes a client against an explicit url, e.g. a local server.
+    ///
+    pub fn from_url(url: String) -> Self {
+        PublicStream {
+            url,
+            reconnect: ReconnectPolicy::default(),
+            heartbeat: Heartbeat::default(),
+        }
+    }
+
+    pub fn with_reconnect_policy(mut self, reconnect: ReconnectPolicy) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    ///
+    /// Opens the socket and spawns the task that drives it.
+    /// Subscribe through the returned handle and read the events off the stream.
+    /// Dropped connections are re-established and resubscribed per the reconnect policy.
+    ///
+    pub async fn connect(&self) -> Result<(StreamHandle, EventStream)> {
+        let session = Session {
+            url: self.url.clone(),
+            credentials: None,
+            reconnect: self.reconnect.clone(),
+            heartbeat: self.heartbeat.clone(),
+            time_sync: Arc::new(TimeSync::new()),
+        };
+        let socket = session.open().await?;
+        Ok(spawn_connection(session, socket))
+    }
+
+    ///
+    /// Connects and subscribes to `topics` in one call.
+    ///
+    /// Example topics:
+    ///     orderbook.50.BTCUSDT, publicTrade.BTCUSDT, tickers.BTCUSDT, kline.5.BTCUSDT
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/ws/connect
+    pub async fn subscribe(&self, topics: Vec<String>) -> Result<(StreamHandle, EventStream)> {
+        let (handle, events) = self.connect().await?;
+        handle.subscribe(topics)?;
+        Ok((handle, events))
+    }
+}
+
+///
+/// Topics of the private stream.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrivateTopic {
+    Order,
+    Execution,
+    Position,
+    Wallet,
+    Greeks,
+}
+
+impl std::fmt::Display for PrivateTopic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PrivateTopic::Order => write!(f, "order"),
+            PrivateTopic::Execution => write!(f, "execution"),
+            PrivateTopic::Position => write!(f, "position"),
+            PrivateTopic::Wallet => write!(f, "wallet"),
+            PrivateTopic::Greeks => write!(f, "greeks"),
+        }
+    }
+}
+
+///
+/// Data pushed on a private topic.
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivateMessage {
+    #[serde(default)]
+    pub id: String,
+    /// Full topic name, e.g. `order` or the category specific `order.linear`.
+    pub topic: String,
+    #[serde(default)]
+    pub creation_time: u64,
+    pub data: Vec<Value>,
+}
+
+impl PrivateMessage {
+    ///
+    /// Deserializes every entry of `data` into `T`.
+    ///
+    pub fn data_as<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+        self.data
+            .iter()
+            .map(|entry| Ok(serde_json::from_value(entry.clone())?))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PrivateEvent {
+    Order(PrivateMessage),
+    Execution(PrivateMessage),
+    Position(PrivateMessage),
+    Wallet(PrivateMessage),
+    Greeks(PrivateMessage),
+    /// Messages on topics this client does not know about yet.
+    Other(PrivateMessage),
+    Response(OpResponse),
+    /// The socket dropped and was re-established, re-authenticated and resubscribed.
+    Reconnected,
+}
+
+impl Event for PrivateEvent {
+    fn reconnected() -> Self {
+        PrivateEvent::Reconnected
+    }
+
+    fn parse(text: &str) -> Result<PrivateEvent> {
+        let value: Value = serde_json::from_str(text)?;
+        if value.get("topic").is_none() {
+            return Ok(PrivateEvent::Response(serde_json::from_value(value)?));
+        }
+        let message: PrivateMessage = serde_json::from_value(value)?;
+        let event = match message.topic.split('.').next().unwrap_or_default() {
+            "order" => PrivateEvent::Order(message),
+            "execution" => PrivateEvent::Execution(message),
+            "position" => PrivateEvent::Position(message),
+            "wallet" => PrivateEvent::Wallet(message),
+            "greeks" => PrivateEvent::Greeks(message),
+            _ => PrivateEvent::Other(message),
+        };
+        Ok(event)
+    }
+}
+
+///
+/// Builds the `auth` op, signing `GET/realtime{expires}` with HMAC-SHA256.
+///
+pub fn auth_message(api_key: &str, api_secret: &str, expires: u128) -> Result<String> {
+    signed_auth_message(api_key, &HmacSigner::new(api_secret), expires)
+}
+
+///
+/// Builds the `auth` op, signing `GET/realtime{expires}` with `signer`.
+///
+pub fn signed_auth_message(api_key: &str, signer: &dyn Signer, expires: u128) -> Result<String> {
+    let signature = signer.sign(&format!("GET/realtime{}", expires))?;
+    Ok(json!({
+        "op": "auth",
+        "args": [api_key, expires, signature],
+    })
+    .to_string())
+}
+
+///
+/// Sends the `auth` op and waits for its reply.
+///
+pub(crate) async fn authenticate(
+    socket: &mut Socket,
+    api_key: &str,
+    signer: &dyn Signer,
+    time_sync: &TimeSync,
+) -> Result<()> {
+    let expires = time_sync.timestamp()? + AUTH_EXPIRE_MS;
+    let message = signed_auth_message(api_key, signer, expires)?;
+    socket.send(Message::Text(message)).await?;
+
+    while let Some(message) = socket.next().await {
+        let text = match message? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let response: OpResponse = match serde_json::from_str(&text) {
+            Ok(response) => response,
+            Err(_) => continue,
+        };
+        if response.op.as_deref() == Some("auth") {
+            if response.success {
+                return Ok(());
+            }
+            return Err(AppError::WebSocketAuthFailed(response.ret_msg));
+        }
+    }
+    Err(AppError::ConnectionClosed(
+        "WebSocket closed before authentication completed".to_owned(),
+    ))
+}
+
+///
+/// Client for the authenticated `/v5/private` stream.
+///
+pub struct PrivateStream {
+    api_key: String,
+    signer: Arc<dyn Signer>,
+    url: String,
+    reconnect: ReconnectPolicy,
+    heartbeat: Heartbeat,
+    time_sync: Arc<TimeSync>,
+}
+
+impl PrivateStream {
+    ///
+    /// Initializes a private stream client on mainnet or testnet.
+    ///
+    pub fn new(api_key: String, api_secret: String, testnet: bool) -> Self {
+        let url = stream_url(&v5websocket::WebSocket::Private.to_string(), testnet);
+        Self::from_url(api_key, api_secret, url)
+    }
+
+    ///
+    /// Initializes a client against an explicit url, e.g. a local server.
+    ///
+    pub fn from_url(api_key: String, api_secret: String, url: String) -> Self {
+        PrivateStream {
+            api_key,
+            signer: Arc::new(HmacSigner::new(&api_secret)),
+            url,
+            reconnect: ReconnectPolicy::default(),
+            heartbeat: Heartbeat::default(),
+            time_sync: Arc::new(TimeSync::new()),
+        }
+    }
+
+    pub fn with_reconnect_policy(mut self, reconnect: ReconnectPolicy) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    ///
+    /// Clock offset for the auth `expires`, usually `HttpManager::time_sync()`.
+    ///
+    pub fn with_time_sync(mut self, time_sync: Arc<TimeSync>) -> Self {
+        self.time_sync = time_sync;
+        self
+    }
+
+    ///
+    /// Signs the auth op with `signer` instead of HMAC with the secret, e.g. for RSA keys.
+    ///
+    pub fn with_signer(mut self, signer: Arc<dyn Signer>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    ///
+    /// Opens the socket and authenticates it. Fails if the keys are rejected.
+    ///
+    pub async fn connect(&self) -> Result<(StreamHandle, EventStream<PrivateEvent>)> {
+        if self.api_key.is_empty() {
+            return Err(AppError::MissingCredentials);
+        }
+        let session = Session {
+            url: self.url.clone(),
+            credentials: Some((self.api_key.clone(), self.signer.clone())),
+            reconnect: self.reconnect.clone(),
+            heartbeat: self.heartbeat.clone(),
+            time_sync: self.time_sync.clone(),
+        };
+        let socket = session.open().await?;
+        Ok(spawn_connection(session, socket))
+    }
+
+    ///
+    /// Connects, authenticates and subscribes to `topics` in one call.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/websocket/private/order
+    pub async fn subscribe(
+        &self,
+        topics: Vec<PrivateTopic>,
+    ) -> Result<(StreamHandle, EventStream<PrivateEvent>)> {
+        let (handle, events) = self.connect().await?;
+        handle.subscribe(topics.iter().map(|t| t.to_string()).collect())?;
+        Ok((handle, events))
+    }
+}
+
+fn op_messages(op: &str, topics: &[String]) -> Vec<Message> {
+    topics
+        .chunks(MAX_TOPICS_PER_REQUEST)
+        .map(|args| Message::Text(json!({ "op": op, "args": args }).to_string()))
+        .collect()
+}
+
+/// Everything the connection task needs to open the socket again.
+struct Session {
+    url: String,
+    credentials: Option<(String, Arc<dyn Signer>)>,
+    reconnect: ReconnectPolicy,
+    heartbeat: Heartbeat,
+    time_sync: Arc<TimeSync>,
+}
+
+impl Session {
+    async fn open(&self) -> Result<Socket> {
+        let (mut socket, _) = connect_async(self.url.as_str()).await?;
+        if let Some((api_key, signer)) = &self.credentials {
+            authenticate(&mut socket, api_key, signer.as_ref(), &self.time_sync).await?;
+        }
+        Ok(socket)
+    }
+}
+
+enum Disconnect {
+    /// Closed through the handle, or the event stream was dropped.
+    Closed,
+    Lost(Error),
+}
+
+fn spawn_connection<E: Event>(session: Session, socket: Socket) -> (StreamHandle, EventStream<E>) {
+    let (command_tx, command_rx) = mpsc::unbounded();
+    let (event_tx, event_rx) = mpsc::unbounded();
+    let health = StreamHealth::default();
+    health.set_connected(true);
+    tokio::spawn(run_connection(
+        session,
+        socket,
+        command_rx,
+        event_tx,
+        health.clone(),
+    ));
+    (
+        StreamHandle {
+            commands: command_tx,
+            health,
+        },
+        event_rx,
+    )
+}
+
+pub(crate) fn ping_message(req_id: u64) -> Message {
+    Message::Text(json!({ "op": "ping", "req_id": req_id.to_string() }).to_string())
+}
+
+///
+/// Public channels answer `{"op":"ping","ret_msg":"pong"}`, the private one `{"op":"pong"}`.
+///
+pub(crate) fn is_pong(text: &str) -> bool {
+    if !text.contains("pong") {
+        return false;
+    }
+    match serde_json::from_str::<OpResponse>(text) {
+        Ok(response) => match response.op.as_deref() {
+            Some("pong") => true,
+            Some("ping") => response.ret_msg == "pong",
+            _ => false,
+        },
+        Err(_) => false,
+    }
+}
+
+async fn run_connection<E: Event>(
+    session: Session,
+    mut socket: Socket,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+    events: mpsc::UnboundedSender<Result<E>>,
+    health: StreamHealth,
+) {
+    let mut subscriptions = BTreeSet::new();
+    loop {
+        let disconnect = drive(
+            &mut socket,
+            &mut commands,
+            &events,
+            &mut subscriptions,
+            &session.heartbeat,
+            &health,
+        )
+        .await;
+        health.set_connected(false);
+        let error = match disconnect {
+            Disconnect::Closed => return,
+            Disconnect::Lost(error) => error,
+        };
+        if events.is_closed() {
+            return;
+        }
+        socket = match reconnect(&session, &mut commands, &mut subscriptions, error).await {
+            Ok(Some(socket)) => socket,
+            Ok(None) => return,
+            Err(error) => {
+                let _ = events.unbounded_send(Err(error));
+                return;
+            }
+        };
+        health.set_connected(true);
+        health.state.reconnects.fetch_add(1, Ordering::Relaxed);
+        if events.unbounded_send(Ok(E::reconnected())).is_err() {
+            let _ = socket.close(None).await;
+            return;
+        }
+    }
+}
+
+///
+/// Pumps commands and messages until the socket drops, goes stale or the stream is closed.
+///
+async fn drive<E: Event>(
+    socket: &mut Socket,
+    commands: &mut mpsc::UnboundedReceiver<Command>,
+    events: &mpsc::UnboundedSender<Result<E>>,
+    subscriptions: &mut BTreeSet<String>,
+    heartbeat: &Heartbeat,
+    health: &StreamHealth,
+) -> Disconnect {
+    let mut ping =
+        tokio::time::interval_at(Instant::now() + heartbeat.interval, heartbeat.interval);
+    ping.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut ping_id = 0u64;
+    let mut ping_sent: Option<Instant> = None;
+    let stale = tokio::time::sleep(heartbeat.stale_after);
+    tokio::pin!(stale);
+
+    loop {
+        tokio::select! {
+            _ = ping.tick() => {
+                ping_id += 1;
+                if let Err(e) = socket.send(ping_message(ping_id)).await {
+                    return Disconnect::Lost(e.into());
+                }
+                ping_sent.get_or_insert_with(Instant::now);
+            }
+            _ = &mut stale => {
+                health.state.stale.store(true, Ordering::Relaxed);
+                return Disconnect::Lost(AppError::Timeout(format!(
+                    "No data received for {:?}",
+                    heartbeat.stale_after
+                )));
+            }
+            command = commands.next() => {
+                let messages = match command {
+                    Some(Command::Subscribe(topics)) => {
+                        subscriptions.extend(topics.iter().cloned());
+                        op_messages("subscribe", &topics)
+                    }
+                    Some(Command::Unsubscribe(topics)) => {
+                        for topic in &topics {
+                            subscriptions.remove(topic);
+                        }
+                        op_messages("unsubscribe", &topics)
+                    }
+                    Some(Command::Close) | None => {
+                        let _ = socket.close(None).await;
+                        return Disconnect::Closed;
+                    }
+                };
+                for message in messages {
+                    if let Err(e) = socket.send(message).await {
+                        return Disconnect::Lost(e.into());
+                    }
+                }
+            }
+            message = socket.next() => {
+                stale.as_mut().reset(Instant::now() + heartbeat.stale_after);
+                health.record_message();
+                let event = match message {
+                    Some(Ok(Message::Text(text))) if is_pong(&text) => {
+                        if let Some(sent) = ping_sent.take() {
+                            health.record_latency(sent.elapsed());
+                        }
+                        continue;
+                    }
+                    Some(Ok(Message::Text(text))) => E::parse(&text),
+                    Some(Ok(Message::Close(_))) | None => {
+                        return Disconnect::Lost(AppError::ConnectionClosed(
+                            "WebSocket closed by server".to_owned(),
+                        ));
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Disconnect::Lost(e.into()),
+                };
+                if events.unbounded_send(event).is_err() {
+                    let _ = socket.close(None).await;
+                    return Disconnect::Closed;
+                }
+            }
+        }
+    }
+}
+
+///
+/// Reopens the session with backoff and resubscribes every tracked topic.
+/// Returns `Ok(None)` if the stream was closed while waiting.
+///
+async fn reconnect(
+    session: &Session,
+    commands: &mut mpsc::UnboundedReceiver<Command>,
+    subscriptions: &mut BTreeSet<String>,
+    mut error: Error,
+) -> Result<Option<Socket>> {
+    let mut attempt = 0;
+    loop {
+        if let Some(max_attempts) = session.reconnect.max_attempts {
+            if attempt >= max_attempts {
+                return Err(error);
+            }
+        }
+
+        let delay = tokio::time::sleep(session.reconnect.backoff(attempt));
+        tokio::pin!(delay);
+        loop {
+            tokio::select! {
+                _ = &mut delay => break,
+                command = commands.next() => match command {
+                    Some(Command::Subscribe(topics)) => subscriptions.extend(topics),
+                    Some(Command::Unsubscribe(topics)) => {
+                        for topic in &topics {
+                            subscriptions.remove(topic);
+                        }
+                    }
+                    Some(Command::Close) | None => return Ok(None),
+                },
+            }
+        }
+        attempt += 1;
+
+        let mut socket = match session.open().await {
+            Ok(socket) => socket,
+            Err(e) => {
+                error = e;
+                continue;
+            }
+        };
+        let topics: Vec<String> = subscriptions.iter().cloned().collect();
+        let mut resubscribed = Ok(());
+        for message in op_messages("subscribe", &topics) {
+            resubscribed = socket.send(message).await;
+            if resubscribed.is_err() {
+                break;
+            }
+        }
+        match resubscribed {
+            Ok(()) => return Ok(Some(socket)),
+            Err(e) => error = e.into(),
+        }
+    }
+}
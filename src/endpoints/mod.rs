@@ -7,3 +7,4 @@ pub mod v5spot_leverage_token;
 pub mod v5spot_margin_trade;
 pub mod v5trade;
 pub mod v5user;
+pub mod v5websocket;
@@ -24,6 +24,11 @@ pub enum Asset {
     GetWithdrawableAmount,
     Withdraw,
     CancelWithdrawal,
+    GetConvertCoinList,
+    RequestConvertQuote,
+    ConfirmConvertQuote,
+    GetConvertStatus,
+    GetConvertHistory,
 }
 
 impl std::fmt::Display for Asset {
@@ -66,6 +71,11 @@ impl std::fmt::Display for Asset {
             Asset::GetWithdrawableAmount => write!(f, "/v5/asset/withdraw/withdrawable-amount"),
             Asset::Withdraw => write!(f, "/v5/asset/withdraw/create"),
             Asset::CancelWithdrawal => write!(f, "/v5/asset/withdraw/cancel"),
+            Asset::GetConvertCoinList => write!(f, "/v5/asset/exchange/query-coin-list"),
+            Asset::RequestConvertQuote => write!(f, "/v5/asset/exchange/quote-apply"),
+            Asset::ConfirmConvertQuote => write!(f, "/v5/asset/exchange/convert-execute"),
+            Asset::GetConvertStatus => write!(f, "/v5/asset/exchange/convert-result-query"),
+            Asset::GetConvertHistory => write!(f, "/v5/asset/exchange/query-convert-history"),
         }
     }
 }
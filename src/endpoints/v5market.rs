@@ -13,6 +13,14 @@ pub enum MarketEnum {
     GetInsurance,
     GetRiskLimit,
     GetOptionDeliveryPrice,
+    GetServerTime,
+    GetLongShortRatio,
+    GetRpiOrderbook,
+    GetOrderPriceLimit,
+    GetIndexPriceComponents,
+    GetNewDeliveryPrice,
+    GetSpotMarginData,
+    GetSpotMarginCollateral,
 }
 
 impl std::fmt::Display for MarketEnum {
@@ -31,10 +39,17 @@ impl std::fmt::Display for MarketEnum {
             MarketEnum::GetPublicTradingHistory => write!(f, "/v5/market/recent-trade"),
             MarketEnum::GetOpenInterest => write!(f, "/v5/market/open-interest"),
             MarketEnum::GetHistoricalVolatility => write!(f, "/v5/market/historical-volatility"),
-            MarketEnum::GetInstrumentsInfo => write!(f, "/v5/market/insurance"),
+            MarketEnum::GetInsurance => write!(f, "/v5/market/insurance"),
             MarketEnum::GetRiskLimit => write!(f, "/v5/market/risk-limit"),
             MarketEnum::GetOptionDeliveryPrice => write!(f, "/v5/market/delivery-price"),
-            MarketEnum::GetInsurance => todo!(),
+            MarketEnum::GetServerTime => write!(f, "/v5/market/time"),
+            MarketEnum::GetLongShortRatio => write!(f, "/v5/market/account-ratio"),
+            MarketEnum::GetRpiOrderbook => write!(f, "/v5/market/rpi_orderbook"),
+            MarketEnum::GetOrderPriceLimit => write!(f, "/v5/market/price-limit"),
+            MarketEnum::GetIndexPriceComponents => write!(f, "/v5/market/index-price-components"),
+            MarketEnum::GetNewDeliveryPrice => write!(f, "/v5/market/new-delivery-price"),
+            MarketEnum::GetSpotMarginData => write!(f, "/v5/spot-margin-trade/data"),
+            MarketEnum::GetSpotMarginCollateral => write!(f, "/v5/spot-margin-trade/collateral"),
         }
     }
 }
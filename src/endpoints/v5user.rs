@@ -8,6 +8,10 @@ pub enum User {
     ModifySubApiKey,
     DeleteMasterApiKey,
     DeleteSubApiKey,
+    GetSubApiKeys,
+    DeleteSubUid,
+    GetUidWalletType,
+    GetAffiliateCustomerInfo,
 }
 
 impl std::fmt::Display for User {
@@ -22,6 +26,10 @@ impl std::fmt::Display for User {
             User::ModifySubApiKey => write!(f, "/v5/user/update-sub-api"),
             User::DeleteMasterApiKey => write!(f, "/v5/user/delete-api"),
             User::DeleteSubApiKey => write!(f, "/v5/user/delete-sub-api"),
+            User::GetSubApiKeys => write!(f, "/v5/user/sub-apikeys"),
+            User::DeleteSubUid => write!(f, "/v5/user/del-submember"),
+            User::GetUidWalletType => write!(f, "/v5/user/get-member-type"),
+            User::GetAffiliateCustomerInfo => write!(f, "/v5/user/aff-customer-info"),
         }
     }
 }
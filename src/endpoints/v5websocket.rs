@@ -0,0 +1,21 @@
+pub enum WebSocket {
+    PublicSpot,
+    PublicLinear,
+    PublicInverse,
+    PublicOption,
+    Private,
+    Trade,
+}
+
+impl std::fmt::Display for WebSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WebSocket::PublicSpot => write!(f, "/v5/public/spot"),
+            WebSocket::PublicLinear => write!(f, "/v5/public/linear"),
+            WebSocket::PublicInverse => write!(f, "/v5/public/inverse"),
+            WebSocket::PublicOption => write!(f, "/v5/public/option"),
+            WebSocket::Private => write!(f, "/v5/private"),
+            WebSocket::Trade => write!(f, "/v5/trade"),
+        }
+    }
+}
@@ -1,34 +1,119 @@
-use std::fmt;
+use std::time::Duration;
 
-#[derive(Debug)]
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
 pub enum AppError {
+    #[error("{0} environment variable not set")]
     EnvVarMissing(String),
-    RequestError(reqwest::Error),
-    JsonError(serde_json::Error),
+    /// The request never got a response: connection, TLS or body read failure.
+    #[error("Request error: {0}")]
+    RequestError(#[from] reqwest::Error),
+    /// The response could not be decoded.
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("HMAC creation error")]
     HmacError,
+    #[error("Signing error: {0}")]
+    SigningError(#[from] openssl::error::ErrorStack),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    /// A non-success HTTP status without a Bybit JSON body.
+    #[error("HTTP {status}: {body}")]
+    HttpStatus { status: u16, body: String },
+    #[error("Authenticated endpoints require keys.")]
+    MissingCredentials,
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Clock error: {0}")]
+    ClockError(#[from] std::time::SystemTimeError),
+    #[error("Query encoding error: {0}")]
+    EncodeError(#[from] serde_urlencoded::ser::Error),
+    #[error("Decimal error: {0}")]
+    DecimalError(#[from] rust_decimal::Error),
+    #[cfg(feature = "archive")]
+    #[error("Parquet error: {0}")]
+    ParquetError(#[from] parquet::errors::ParquetError),
+    #[error("WebSocket error: {0}")]
+    WebSocketError(Box<tokio_tungstenite::tungstenite::Error>),
+    #[error("WebSocket authentication failed: {0}")]
+    WebSocketAuthFailed(String),
+    #[error("Connection closed: {0}")]
+    ConnectionClosed(String),
+    #[error("Timed out: {0}")]
+    Timeout(String),
+    /// Rejected by the client-side rate limiter without being sent.
+    #[error("Rate limit for {path} reached, retry after {retry_after:?}")]
+    Throttled { path: String, retry_after: Duration },
+    /// A convert quote had expired, or was about to, and was not confirmed.
+    #[error("Convert quote {quote_tx_id} expires at {expired_time}")]
+    QuoteExpired {
+        quote_tx_id: String,
+        expired_time: DateTime<Utc>,
+    },
+
+    /// Bybit rejected the request (`retCode != 0`) with a code without a dedicated variant.
+    #[error("API error {ret_code}: {ret_msg}")]
+    Api {
+        ret_code: i64,
+        ret_msg: String,
+        ret_ext_info: Value,
+    },
+    #[error("Insufficient balance ({ret_code}): {ret_msg}")]
+    InsufficientBalance { ret_code: i64, ret_msg: String },
+    #[error("Rate limited ({ret_code}): {ret_msg}")]
+    RateLimited { ret_code: i64, ret_msg: String },
+    #[error("Invalid signature ({ret_code}): {ret_msg}")]
+    InvalidSignature { ret_code: i64, ret_msg: String },
+    #[error("Timestamp outside recv_window ({ret_code}): {ret_msg}")]
+    TimestampOutOfRecvWindow { ret_code: i64, ret_msg: String },
+    #[error("Order not found ({ret_code}): {ret_msg}")]
+    OrderNotFound { ret_code: i64, ret_msg: String },
 }
 
-impl fmt::Display for AppError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            AppError::EnvVarMissing(var_name) => {
-                write!(f, "{} environment variable not set", var_name)
+impl AppError {
+    ///
+    /// Maps a Bybit `retCode` to its variant, falling back to `AppError::Api`.
+    ///
+    /// Additional information:
+    ///     https://bybit-exchange.github.io/docs/v5/error
+    pub fn from_ret_code(ret_code: i64, ret_msg: String, ret_ext_info: Value) -> AppError {
+        match ret_code {
+            10002 => AppError::TimestampOutOfRecvWindow { ret_code, ret_msg },
+            10004 => AppError::InvalidSignature { ret_code, ret_msg },
+            10006 | 10018 => AppError::RateLimited { ret_code, ret_msg },
+            110004 | 110007 | 110012 | 170131 => {
+                AppError::InsufficientBalance { ret_code, ret_msg }
             }
-            AppError::RequestError(err) => write!(f, "Request error: {}", err),
-            AppError::JsonError(err) => write!(f, "JSON error: {}", err),
-            AppError::HmacError => write!(f, "HMAC creation error"),
+            110001 | 170213 => AppError::OrderNotFound { ret_code, ret_msg },
+            _ => AppError::Api {
+                ret_code,
+                ret_msg,
+                ret_ext_info,
+            },
         }
     }
-}
 
-impl From<reqwest::Error> for AppError {
-    fn from(err: reqwest::Error) -> Self {
-        AppError::RequestError(err)
+    ///
+    /// The Bybit `retCode` for exchange rejections, `None` for local and transport failures.
+    ///
+    pub fn ret_code(&self) -> Option<i64> {
+        match self {
+            AppError::Api { ret_code, .. }
+            | AppError::InsufficientBalance { ret_code, .. }
+            | AppError::RateLimited { ret_code, .. }
+            | AppError::InvalidSignature { ret_code, .. }
+            | AppError::TimestampOutOfRecvWindow { ret_code, .. }
+            | AppError::OrderNotFound { ret_code, .. } => Some(*ret_code),
+            _ => None,
+        }
     }
 }
 
-impl From<serde_json::Error> for AppError {
-    fn from(err: serde_json::Error) -> Self {
-        AppError::JsonError(err)
+impl From<tokio_tungstenite::tungstenite::Error> for AppError {
+    fn from(error: tokio_tungstenite::tungstenite::Error) -> Self {
+        AppError::WebSocketError(Box::new(error))
     }
 }
@@ -19,8 +19,7 @@ pub fn generate_timestamp() -> Result<u128, std::time::SystemTimeError> {
 }
 
 ///
-///
-///
+/// Hex encoded HMAC-SHA256 of `query_string`.
 ///
 pub fn sign_query_string(query_string: &str, secret: &str) -> Result<String, AppError> {
     let mut mac = Hmac::<Sha256>::new_varkey(secret.as_bytes()).map_err(|_| AppError::HmacError)?;
@@ -29,13 +28,20 @@ pub fn sign_query_string(query_string: &str, secret: &str) -> Result<String, App
 }
 
 ///
+/// Url-encodes parameters sorted by key, so the string that is signed is the one that is sent.
 ///
+pub fn canonical_query_string(parameters: &HashMap<String, String>) -> Result<String, AppError> {
+    let sorted: BTreeMap<&String, &String> = parameters.iter().collect();
+    Ok(serde_urlencoded::to_string(sorted)?)
+}
+
+///
+/// Lowercase hex of `bytes`.
 ///
 fn bytes_to_hex(bytes: Vec<u8>) -> String {
     bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>()
 }
 
-///
 ///
 /// Build custom headers
 /// Params
@@ -43,19 +49,22 @@ fn bytes_to_hex(bytes: Vec<u8>) -> String {
 ///     timestamp :  u64
 ///     api_key: &str
 ///     recv_window:  &str
+///     sign_type: Option<&str>, left out when None
 /// Returns:  HeaderMap
 ///
-///
 pub fn build_private_headers(
     api_key: &str,
     signature: &str,
     timestamp: u128,
     recv_window: &str,
+    sign_type: Option<&'static str>,
 ) -> HeaderMap {
     let mut headers = HeaderMap::new();
     headers.insert("X-BAPI-API-KEY", HeaderValue::from_str(api_key).unwrap());
     headers.insert("X-BAPI-SIGN", HeaderValue::from_str(signature).unwrap());
-    headers.insert("X-BAPI-SIGN-TYPE", HeaderValue::from_static("2"));
+    if let Some(sign_type) = sign_type {
+        headers.insert("X-BAPI-SIGN-TYPE", HeaderValue::from_static(sign_type));
+    }
     headers.insert(
         "X-BAPI-TIMESTAMP",
         HeaderValue::from_str(&timestamp.to_string()).expect("Invalid timestamp"),
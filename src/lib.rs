@@ -4,3 +4,4 @@ pub mod bybit;
 pub mod endpoints;
 pub mod errors;
 pub mod helpers;
+pub mod models;
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde_derive::Deserialize;
+
+use super::{de, enums::Side};
+
+///
+/// One account of `/v5/account/wallet-balance`. Account level totals are only
+/// calculated for unified accounts and are `None` otherwise.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/account/wallet-balance
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletBalance {
+    /// `UNIFIED`, `CONTRACT` or `SPOT`.
+    pub account_type: String,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub total_equity: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub total_wallet_balance: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub total_margin_balance: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub total_available_balance: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub total_perp_upl: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub total_initial_margin: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub total_maintenance_margin: Option<Decimal>,
+    #[serde(
+        rename = "accountIMRate",
+        default,
+        deserialize_with = "de::decimal_opt"
+    )]
+    pub account_im_rate: Option<Decimal>,
+    #[serde(
+        rename = "accountMMRate",
+        default,
+        deserialize_with = "de::decimal_opt"
+    )]
+    pub account_mm_rate: Option<Decimal>,
+    #[serde(rename = "accountLTV", default, deserialize_with = "de::decimal_opt")]
+    pub account_ltv: Option<Decimal>,
+    pub coin: Vec<CoinBalance>,
+}
+
+impl WalletBalance {
+    pub fn coin(&self, coin: &str) -> Option<&CoinBalance> {
+        self.coin.iter().find(|balance| balance.coin == coin)
+    }
+}
+
+///
+/// Balance of one coin. Amounts that do not apply to the account type are zero.
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinBalance {
+    pub coin: String,
+    pub equity: Decimal,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub usd_value: Decimal,
+    pub wallet_balance: Decimal,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub unrealised_pnl: Decimal,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub cum_realised_pnl: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub locked: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub bonus: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub borrow_amount: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub accrued_interest: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub available_to_borrow: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub available_to_withdraw: Decimal,
+    #[serde(
+        rename = "totalOrderIM",
+        default,
+        deserialize_with = "de::decimal_or_zero"
+    )]
+    pub total_order_im: Decimal,
+    #[serde(
+        rename = "totalPositionIM",
+        default,
+        deserialize_with = "de::decimal_or_zero"
+    )]
+    pub total_position_im: Decimal,
+    #[serde(
+        rename = "totalPositionMM",
+        default,
+        deserialize_with = "de::decimal_or_zero"
+    )]
+    pub total_position_mm: Decimal,
+    #[serde(default)]
+    pub margin_collateral: bool,
+    #[serde(default)]
+    pub collateral_switch: bool,
+}
+
+///
+/// One entry of `/v5/account/transaction-log`. Trade fields are `None` for
+/// transfers, bonuses and other non-trade entries.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/account/transaction-log
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionLogEntry {
+    pub id: String,
+    #[serde(default)]
+    pub symbol: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default, deserialize_with = "de::enum_opt")]
+    pub side: Option<Side>,
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub transaction_time: DateTime<Utc>,
+    /// `TRADE`, `SETTLEMENT`, `DELIVERY`, `TRANSFER_IN`, `TRANSFER_OUT`, ...
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub currency: String,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub qty: Option<Decimal>,
+    /// Position size after this entry.
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub size: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub trade_price: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub funding: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub fee_rate: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub fee: Decimal,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub cash_flow: Decimal,
+    /// Net change of the wallet balance: `cash_flow - fee - funding`.
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub change: Decimal,
+    pub cash_balance: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub bonus_change: Decimal,
+    #[serde(default)]
+    pub trade_id: String,
+    #[serde(default)]
+    pub order_id: String,
+    #[serde(default)]
+    pub order_link_id: String,
+}
@@ -0,0 +1,196 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+
+use super::{
+    de,
+    enums::{ConvertAccount, ConvertStatus},
+};
+
+///
+/// Typed arguments of `/v5/asset/exchange/quote-apply`. Build with `sell` to fix the
+/// amount paid in `from_coin`, or `buy` to fix the amount received in `to_coin`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/asset/convert/apply-quote
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertQuoteRequest {
+    pub account_type: ConvertAccount,
+    pub from_coin: String,
+    pub to_coin: String,
+    /// `from_coin` or `to_coin`, whichever `request_amount` is in.
+    pub request_coin: String,
+    pub request_amount: Decimal,
+    /// `crypto` for regular coins, `fiat` for fiat.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_coin_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_coin_type: Option<String>,
+    /// `opFrom`, for brokers that pass their broker id in `param_value`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub param_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub param_value: Option<String>,
+    /// Client id of the quote, echoed back in `ConvertQuote::request_id`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ConvertQuoteRequest {
+    pub fn sell(
+        account_type: ConvertAccount,
+        from_coin: &str,
+        to_coin: &str,
+        amount: Decimal,
+    ) -> Self {
+        ConvertQuoteRequest::new(account_type, from_coin, to_coin, from_coin, amount)
+    }
+
+    pub fn buy(
+        account_type: ConvertAccount,
+        from_coin: &str,
+        to_coin: &str,
+        amount: Decimal,
+    ) -> Self {
+        ConvertQuoteRequest::new(account_type, from_coin, to_coin, to_coin, amount)
+    }
+
+    fn new(
+        account_type: ConvertAccount,
+        from_coin: &str,
+        to_coin: &str,
+        request_coin: &str,
+        request_amount: Decimal,
+    ) -> Self {
+        ConvertQuoteRequest {
+            account_type,
+            from_coin: from_coin.to_owned(),
+            to_coin: to_coin.to_owned(),
+            request_coin: request_coin.to_owned(),
+            request_amount,
+            from_coin_type: None,
+            to_coin_type: None,
+            param_type: None,
+            param_value: None,
+            request_id: None,
+        }
+    }
+
+    pub fn request_id(mut self, request_id: &str) -> Self {
+        self.request_id = Some(request_id.to_owned());
+        self
+    }
+}
+
+///
+/// `result` of `/v5/asset/exchange/quote-apply`. The quote can be confirmed until `expired_time`.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertQuote {
+    pub quote_tx_id: String,
+    pub exchange_rate: Decimal,
+    pub from_coin: String,
+    #[serde(default)]
+    pub from_coin_type: String,
+    pub to_coin: String,
+    #[serde(default)]
+    pub to_coin_type: String,
+    pub from_amount: Decimal,
+    pub to_amount: Decimal,
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub expired_time: DateTime<Utc>,
+    #[serde(default)]
+    pub request_id: String,
+}
+
+///
+/// `result` of `/v5/asset/exchange/convert-execute`.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertConfirmation {
+    pub quote_tx_id: String,
+    pub exchange_status: ConvertStatus,
+}
+
+///
+/// A confirmed quote, as returned by `Asset::convert`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conversion {
+    pub quote: ConvertQuote,
+    pub exchange_status: ConvertStatus,
+}
+
+///
+/// A coin that can be converted, with its limits per order in `/v5/asset/exchange/query-coin-list`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/asset/convert/convert-coin-list
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertCoin {
+    pub coin: String,
+    #[serde(default)]
+    pub full_name: String,
+    #[serde(default)]
+    pub coin_type: String,
+    /// Decimal places of amounts in this coin.
+    #[serde(default)]
+    pub accuracy_length: u32,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub balance: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub single_from_min_limit: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub single_from_max_limit: Decimal,
+    #[serde(default)]
+    pub disable_from: bool,
+    #[serde(default)]
+    pub disable_to: bool,
+}
+
+///
+/// `result` of `/v5/asset/exchange/query-coin-list`.
+///
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConvertCoins {
+    pub coins: Vec<ConvertCoin>,
+}
+
+///
+/// A convert, as returned by `/v5/asset/exchange/convert-result-query` and
+/// `/v5/asset/exchange/query-convert-history`.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertRecord {
+    pub account_type: ConvertAccount,
+    pub exchange_tx_id: String,
+    pub from_coin: String,
+    pub to_coin: String,
+    pub from_amount: Decimal,
+    pub to_amount: Decimal,
+    pub exchange_status: ConvertStatus,
+    pub convert_rate: Decimal,
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub created_at: DateTime<Utc>,
+}
+
+///
+/// `result` of `/v5/asset/exchange/convert-result-query`, which puts the record under `result`.
+///
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConvertStatusResult {
+    pub result: ConvertRecord,
+}
+
+///
+/// `result` of `/v5/asset/exchange/query-convert-history`.
+///
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConvertHistory {
+    pub list: Vec<ConvertRecord>,
+}
@@ -0,0 +1,148 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use serde::{de, Deserialize, Deserializer};
+use serde_derive::Deserialize;
+
+///
+/// Bybit sends numbers either as JSON numbers or as strings, depending on the endpoint.
+/// JSON numbers keep their original text (serde_json `arbitrary_precision`), so no digits
+/// are lost on the way to a `Decimal`.
+///
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Raw {
+    Str(String),
+    Number(serde_json::Number),
+}
+
+impl Raw {
+    fn into_string(self) -> String {
+        match self {
+            Raw::Str(value) => value,
+            Raw::Number(value) => value.to_string(),
+        }
+    }
+}
+
+fn from_millis<E: de::Error>(value: &str) -> Result<DateTime<Utc>, E> {
+    let millis = i64::from_str(value).map_err(de::Error::custom)?;
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .ok_or_else(|| de::Error::custom(format!("Timestamp out of range {}", millis)))
+}
+
+///
+/// Epoch milliseconds, as a string or a number.
+///
+pub fn timestamp_ms<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    from_millis(&Raw::deserialize(deserializer)?.into_string())
+}
+
+///
+/// Epoch milliseconds where `""` and `"0"` mean not set.
+///
+pub fn timestamp_ms_opt<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error> {
+    match Option::<Raw>::deserialize(deserializer)?.map(Raw::into_string) {
+        None => Ok(None),
+        Some(value) if value.is_empty() || value == "0" => Ok(None),
+        Some(value) => from_millis(&value).map(Some),
+    }
+}
+
+///
+/// A decimal where `""` means not set.
+///
+pub fn decimal_opt<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Decimal>, D::Error> {
+    match Option::<Raw>::deserialize(deserializer)?.map(Raw::into_string) {
+        None => Ok(None),
+        Some(value) if value.is_empty() => Ok(None),
+        Some(value) => parse(&value).map(Some),
+    }
+}
+
+///
+/// A number sent as a string, such as `"priceScale": "2"`.
+///
+pub fn number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    parse(&Raw::deserialize(deserializer)?.into_string())
+}
+
+///
+/// `number` where `""` means not set.
+///
+pub fn number_opt<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    match Option::<Raw>::deserialize(deserializer)?.map(Raw::into_string) {
+        None => Ok(None),
+        Some(value) if value.is_empty() => Ok(None),
+        Some(value) => parse(&value).map(Some),
+    }
+}
+
+fn parse<T, E>(value: &str) -> Result<T, E>
+where
+    T: FromStr,
+    T::Err: Display,
+    E: de::Error,
+{
+    T::from_str(value).map_err(|e| de::Error::custom(format!("{} ({:?})", e, value)))
+}
+
+///
+/// A decimal where `""` means zero, as Bybit sends for balances that do not apply.
+///
+pub fn decimal_or_zero<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+    Ok(decimal_opt(deserializer)?.unwrap_or_default())
+}
+
+///
+/// An enum where `""` and `"None"` mean not set, such as the side of a flat position.
+///
+pub fn enum_opt<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(value) if value.is_empty() || value == "None" => Ok(None),
+        Some(value) => parse(&value).map(Some),
+    }
+}
+
+///
+/// A flag sent as `true`/`false`, `0`/`1` or `"0"`/`"1"`, depending on the endpoint.
+///
+pub fn flag<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Other(Raw),
+    }
+
+    match Flag::deserialize(deserializer)? {
+        Flag::Bool(value) => Ok(value),
+        Flag::Other(raw) => match raw.into_string().as_str() {
+            "0" => Ok(false),
+            "1" => Ok(true),
+            other => Err(de::Error::custom(format!("Invalid flag {:?}", other))),
+        },
+    }
+}
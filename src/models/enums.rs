@@ -0,0 +1,248 @@
+use std::{convert::TryFrom, fmt, str::FromStr, time::Duration};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::errors::app_error::AppError;
+
+///
+/// Declares a string enum that serializes, displays and parses as the value Bybit uses.
+///
+macro_rules! api_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $value:literal,)+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $value,)+
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = AppError;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                match value {
+                    $($value => Ok($name::$variant),)+
+                    other => Err(AppError::InvalidInput(format!(
+                        "Unknown {} {:?}",
+                        stringify!($name),
+                        other
+                    ))),
+                }
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                value.parse().map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+api_enum! {
+    /// Product type.
+    pub enum Category {
+        Spot => "spot",
+        Linear => "linear",
+        Inverse => "inverse",
+        Option => "option",
+    }
+}
+
+api_enum! {
+    pub enum Side {
+        Buy => "Buy",
+        Sell => "Sell",
+    }
+}
+
+api_enum! {
+    pub enum OrderType {
+        Market => "Market",
+        Limit => "Limit",
+    }
+}
+
+api_enum! {
+    pub enum TimeInForce {
+        /// Good till cancelled.
+        GoodTillCancel => "GTC",
+        /// Immediate or cancel.
+        ImmediateOrCancel => "IOC",
+        /// Fill or kill.
+        FillOrKill => "FOK",
+        /// Maker only; cancelled if it would take liquidity.
+        PostOnly => "PostOnly",
+    }
+}
+
+api_enum! {
+    /// Kline interval.
+    pub enum Interval {
+        Minute1 => "1",
+        Minute3 => "3",
+        Minute5 => "5",
+        Minute15 => "15",
+        Minute30 => "30",
+        Hour1 => "60",
+        Hour2 => "120",
+        Hour4 => "240",
+        Hour6 => "360",
+        Hour12 => "720",
+        Day => "D",
+        Week => "W",
+        Month => "M",
+    }
+}
+
+impl Interval {
+    ///
+    /// Length of one bar, `None` for months.
+    ///
+    pub fn duration(&self) -> Option<Duration> {
+        let minutes = match self {
+            Interval::Minute1 => 1,
+            Interval::Minute3 => 3,
+            Interval::Minute5 => 5,
+            Interval::Minute15 => 15,
+            Interval::Minute30 => 30,
+            Interval::Hour1 => 60,
+            Interval::Hour2 => 120,
+            Interval::Hour4 => 240,
+            Interval::Hour6 => 360,
+            Interval::Hour12 => 720,
+            Interval::Day => 24 * 60,
+            Interval::Week => 7 * 24 * 60,
+            Interval::Month => return None,
+        };
+        Some(Duration::from_secs(minutes * 60))
+    }
+}
+
+api_enum! {
+    /// Price used to trigger conditional orders and TP/SL.
+    pub enum TriggerBy {
+        LastPrice => "LastPrice",
+        IndexPrice => "IndexPrice",
+        MarkPrice => "MarkPrice",
+    }
+}
+
+api_enum! {
+    /// `Full` closes the whole position at TP/SL, `Partial` closes `tpSize`/`slSize`.
+    pub enum TpslMode {
+        Full => "Full",
+        Partial => "Partial",
+    }
+}
+
+api_enum! {
+    /// Self-match prevention.
+    pub enum SmpType {
+        None => "None",
+        CancelMaker => "CancelMaker",
+        CancelTaker => "CancelTaker",
+        CancelBoth => "CancelBoth",
+    }
+}
+
+api_enum! {
+    /// Wallet a convert is paid from and credited to.
+    pub enum ConvertAccount {
+        Funding => "eb_convert_funding",
+        Unified => "eb_convert_uta",
+        Spot => "eb_convert_spot",
+        Contract => "eb_convert_contract",
+        Inverse => "eb_convert_inverse",
+    }
+}
+
+api_enum! {
+    /// State of a confirmed convert quote.
+    pub enum ConvertStatus {
+        Init => "init",
+        Processing => "processing",
+        Success => "success",
+        Failure => "failure",
+    }
+}
+
+///
+/// Position side in hedge mode. One-way mode uses `OneWay`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PositionIdx {
+    OneWay,
+    HedgeBuy,
+    HedgeSell,
+}
+
+impl PositionIdx {
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            PositionIdx::OneWay => 0,
+            PositionIdx::HedgeBuy => 1,
+            PositionIdx::HedgeSell => 2,
+        }
+    }
+}
+
+impl fmt::Display for PositionIdx {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_u8())
+    }
+}
+
+impl TryFrom<u8> for PositionIdx {
+    type Error = AppError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PositionIdx::OneWay),
+            1 => Ok(PositionIdx::HedgeBuy),
+            2 => Ok(PositionIdx::HedgeSell),
+            other => Err(AppError::InvalidInput(format!(
+                "Unknown PositionIdx {}",
+                other
+            ))),
+        }
+    }
+}
+
+impl Serialize for PositionIdx {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.as_u8())
+    }
+}
+
+impl<'de> Deserialize<'de> for PositionIdx {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = u8::deserialize(deserializer)?;
+        PositionIdx::try_from(value).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,547 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde_derive::Deserialize;
+
+use crate::errors::app_error::AppError;
+
+use super::{
+    de,
+    enums::{Category, OrderType, Side},
+    Page,
+};
+
+///
+/// `result` of the kline endpoints. `K` is `Kline` for `/v5/market/kline`
+/// and `PriceKline` for the mark, index and premium index klines.
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Klines<K> {
+    pub category: Category,
+    pub symbol: String,
+    pub list: Vec<K>,
+}
+
+///
+/// One candle, sent as `[startTime, open, high, low, close, volume, turnover]`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/market/kline
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Kline {
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub start_time: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    /// Base coin for USDT contracts, contracts for inverse.
+    pub volume: Decimal,
+    /// Quote coin for USDT contracts, base coin for inverse.
+    pub turnover: Decimal,
+}
+
+///
+/// Mark, index or premium index candle, sent as `[startTime, open, high, low, close]`.
+///
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PriceKline {
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub start_time: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+}
+
+///
+/// `[price, size]` of an order book level.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct PriceLevel {
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+///
+/// `result` of `/v5/market/orderbook`. Bids are best first (descending), asks ascending.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/market/orderbook
+#[derive(Debug, Clone, Deserialize)]
+pub struct Orderbook {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "b")]
+    pub bids: Vec<PriceLevel>,
+    #[serde(rename = "a")]
+    pub asks: Vec<PriceLevel>,
+    #[serde(rename = "ts", deserialize_with = "de::timestamp_ms")]
+    pub timestamp: DateTime<Utc>,
+    #[serde(rename = "u")]
+    pub update_id: u64,
+    #[serde(default)]
+    pub seq: u64,
+    /// Matching engine time of the last update, when Bybit sends it.
+    #[serde(default, rename = "cts", deserialize_with = "de::timestamp_ms_opt")]
+    pub created_time: Option<DateTime<Utc>>,
+}
+
+///
+/// `result` of `/v5/market/tickers`, one variant per ticker shape.
+/// Inverse tickers share the linear shape.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/market/tickers
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "category", content = "list", rename_all = "lowercase")]
+pub enum Tickers {
+    Spot(Vec<SpotTicker>),
+    #[serde(alias = "inverse")]
+    Linear(Vec<LinearTicker>),
+    Option(Vec<OptionTicker>),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotTicker {
+    pub symbol: String,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub bid1_price: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub bid1_size: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub ask1_price: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub ask1_size: Option<Decimal>,
+    pub last_price: Decimal,
+    pub prev_price24h: Decimal,
+    pub price24h_pcnt: Decimal,
+    pub high_price24h: Decimal,
+    pub low_price24h: Decimal,
+    pub turnover24h: Decimal,
+    pub volume24h: Decimal,
+    /// USD index price; only sent for some coins.
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub usd_index_price: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearTicker {
+    pub symbol: String,
+    pub last_price: Decimal,
+    pub index_price: Decimal,
+    pub mark_price: Decimal,
+    pub prev_price24h: Decimal,
+    pub price24h_pcnt: Decimal,
+    pub high_price24h: Decimal,
+    pub low_price24h: Decimal,
+    pub prev_price1h: Decimal,
+    pub open_interest: Decimal,
+    pub open_interest_value: Decimal,
+    pub turnover24h: Decimal,
+    pub volume24h: Decimal,
+    /// Empty for delivery contracts.
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub funding_rate: Option<Decimal>,
+    #[serde(deserialize_with = "de::timestamp_ms_opt")]
+    pub next_funding_time: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub predicted_delivery_price: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub basis_rate: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub basis: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub delivery_fee_rate: Option<Decimal>,
+    /// Only set for delivery contracts.
+    #[serde(default, deserialize_with = "de::timestamp_ms_opt")]
+    pub delivery_time: Option<DateTime<Utc>>,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub bid1_price: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub bid1_size: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub ask1_price: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub ask1_size: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionTicker {
+    pub symbol: String,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub bid1_price: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub bid1_size: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub bid1_iv: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub ask1_price: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub ask1_size: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_opt")]
+    pub ask1_iv: Option<Decimal>,
+    pub last_price: Decimal,
+    pub high_price24h: Decimal,
+    pub low_price24h: Decimal,
+    pub mark_price: Decimal,
+    pub index_price: Decimal,
+    pub mark_iv: Decimal,
+    pub underlying_price: Decimal,
+    pub open_interest: Decimal,
+    pub turnover24h: Decimal,
+    pub volume24h: Decimal,
+    pub total_volume: Decimal,
+    pub total_turnover: Decimal,
+    pub delta: Decimal,
+    pub gamma: Decimal,
+    pub vega: Decimal,
+    pub theta: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub predicted_delivery_price: Option<Decimal>,
+    pub change24h: Decimal,
+}
+
+///
+/// One entry of `/v5/market/instruments-info`. The spot, derivatives and option
+/// shapes are merged; fields a category does not send are `None`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/market/instrument
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentInfo {
+    pub symbol: String,
+    pub status: String,
+    pub base_coin: String,
+    pub quote_coin: String,
+    #[serde(default)]
+    pub settle_coin: Option<String>,
+    /// `LinearPerpetual`, `LinearFutures`, `InversePerpetual` or `InverseFutures`.
+    #[serde(default)]
+    pub contract_type: Option<String>,
+    /// `Call` or `Put`.
+    #[serde(default)]
+    pub options_type: Option<String>,
+    #[serde(default, deserialize_with = "de::timestamp_ms_opt")]
+    pub launch_time: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "de::timestamp_ms_opt")]
+    pub delivery_time: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub delivery_fee_rate: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::number_opt")]
+    pub price_scale: Option<u32>,
+    #[serde(default)]
+    pub leverage_filter: Option<LeverageFilter>,
+    pub price_filter: PriceFilter,
+    pub lot_size_filter: LotSizeFilter,
+    /// Minutes between funding settlements.
+    #[serde(default)]
+    pub funding_interval: Option<u64>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub upper_funding_rate: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub lower_funding_rate: Option<Decimal>,
+    /// Spot only: `none`, `both`, `utaOnly` or `normalSpotOnly`.
+    #[serde(default)]
+    pub margin_trading: Option<String>,
+}
+
+impl InstrumentInfo {
+    ///
+    /// Quantity increment: `qtyStep`, or `basePrecision` for spot.
+    ///
+    pub fn qty_step(&self) -> Option<Decimal> {
+        self.lot_size_filter
+            .qty_step
+            .or(self.lot_size_filter.base_precision)
+    }
+
+    ///
+    /// Rounds to the nearest multiple of `tickSize`.
+    ///
+    pub fn round_price(&self, price: Decimal) -> Decimal {
+        round_to(price, self.price_filter.tick_size, RoundingStrategy::MidpointAwayFromZero)
+    }
+
+    ///
+    /// Rounds down to a multiple of the quantity step, so the order never grows.
+    ///
+    pub fn round_qty(&self, qty: Decimal) -> Decimal {
+        match self.qty_step() {
+            Some(step) => round_to(qty, step, RoundingStrategy::ToZero),
+            None => qty,
+        }
+    }
+
+    ///
+    /// Checks an order against the price and lot size filters.
+    /// `price` is the limit price, or the expected fill price of a market order for the
+    /// notional checks; `None` skips them.
+    ///
+    pub fn check_order(
+        &self,
+        order_type: OrderType,
+        qty: Decimal,
+        price: Option<Decimal>,
+    ) -> Result<(), AppError> {
+        let invalid = |message: String| Err(AppError::InvalidInput(message));
+        let lots = &self.lot_size_filter;
+
+        if qty < lots.min_order_qty {
+            return invalid(format!(
+                "{} qty {} is below the minimum {}",
+                self.symbol, qty, lots.min_order_qty
+            ));
+        }
+        let max_qty = match (order_type, lots.max_mkt_order_qty) {
+            (OrderType::Market, Some(max)) => max,
+            _ => lots.max_order_qty,
+        };
+        if qty > max_qty {
+            return invalid(format!(
+                "{} qty {} is above the maximum {}",
+                self.symbol, qty, max_qty
+            ));
+        }
+        if let Some(step) = self.qty_step() {
+            if !step.is_zero() && !(qty % step).is_zero() {
+                return invalid(format!(
+                    "{} qty {} is not a multiple of {}",
+                    self.symbol, qty, step
+                ));
+            }
+        }
+
+        let price = match price {
+            Some(price) => price,
+            None => return Ok(()),
+        };
+        let prices = &self.price_filter;
+        if order_type == OrderType::Limit {
+            if !prices.tick_size.is_zero() && !(price % prices.tick_size).is_zero() {
+                return invalid(format!(
+                    "{} price {} is not a multiple of {}",
+                    self.symbol, price, prices.tick_size
+                ));
+            }
+            let below = prices.min_price.map_or(false, |min| price < min);
+            let above = prices.max_price.map_or(false, |max| price > max);
+            if below || above {
+                return invalid(format!(
+                    "{} price {} is outside the allowed range",
+                    self.symbol, price
+                ));
+            }
+        }
+        self.check_order_value(qty * price)
+    }
+
+    ///
+    /// Checks an order value in the quote coin against the minimum notional
+    /// (`minNotionalValue`, or `minOrderAmt` for spot) and `maxOrderAmt`.
+    ///
+    pub fn check_order_value(&self, value: Decimal) -> Result<(), AppError> {
+        let lots = &self.lot_size_filter;
+        if let Some(min) = lots.min_notional_value.or(lots.min_order_amt) {
+            if value < min {
+                return Err(AppError::InvalidInput(format!(
+                    "{} order value {} is below the minimum {}",
+                    self.symbol, value, min
+                )));
+            }
+        }
+        if let Some(max) = lots.max_order_amt {
+            if value > max {
+                return Err(AppError::InvalidInput(format!(
+                    "{} order value {} is above the maximum {}",
+                    self.symbol, value, max
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn round_to(value: Decimal, step: Decimal, strategy: RoundingStrategy) -> Decimal {
+    if step.is_zero() {
+        return value;
+    }
+    let rounded = (value / step).round_dp_with_strategy(0, strategy) * step;
+    rounded.round_dp(step.scale())
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageFilter {
+    pub min_leverage: Decimal,
+    pub max_leverage: Decimal,
+    pub leverage_step: Decimal,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceFilter {
+    pub tick_size: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub min_price: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub max_price: Option<Decimal>,
+}
+
+///
+/// Order size limits. Derivatives and options step quantities by `qty_step`,
+/// spot by `base_precision`.
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LotSizeFilter {
+    pub min_order_qty: Decimal,
+    pub max_order_qty: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub qty_step: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub base_precision: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub quote_precision: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub min_order_amt: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub max_order_amt: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub max_mkt_order_qty: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub post_only_max_order_qty: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub min_notional_value: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRate {
+    pub symbol: String,
+    pub funding_rate: Decimal,
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub funding_rate_timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicTrade {
+    pub exec_id: String,
+    pub symbol: String,
+    pub price: Decimal,
+    pub size: Decimal,
+    /// Taker side.
+    pub side: Side,
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub time: DateTime<Utc>,
+    #[serde(default)]
+    pub is_block_trade: bool,
+}
+
+///
+/// `result` of `/v5/market/open-interest`.
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterestPage {
+    pub category: Category,
+    pub symbol: String,
+    pub list: Vec<OpenInterest>,
+    #[serde(default)]
+    pub next_page_cursor: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterest {
+    pub open_interest: Decimal,
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub timestamp: DateTime<Utc>,
+}
+
+///
+/// One entry of `/v5/market/historical-volatility`, whose `result` is a plain array.
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoricalVolatility {
+    /// Window in days.
+    pub period: u32,
+    pub value: Decimal,
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub time: DateTime<Utc>,
+}
+
+///
+/// `result` of `/v5/market/insurance`.
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Insurance {
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub updated_time: DateTime<Utc>,
+    pub list: Vec<InsuranceFund>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsuranceFund {
+    pub coin: String,
+    pub balance: Decimal,
+    /// USD value of the fund.
+    pub value: Decimal,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskLimit {
+    pub id: u64,
+    pub symbol: String,
+    pub risk_limit_value: Decimal,
+    pub maintenance_margin: Decimal,
+    pub initial_margin: Decimal,
+    #[serde(deserialize_with = "de::number")]
+    pub is_lowest_risk: u8,
+    pub max_leverage: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub mm_deduction: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryPrice {
+    pub symbol: String,
+    pub delivery_price: Decimal,
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub delivery_time: DateTime<Utc>,
+}
+
+///
+/// One entry of `/v5/market/account-ratio`: the share of users long and short.
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LongShortRatio {
+    pub symbol: String,
+    pub buy_ratio: Decimal,
+    pub sell_ratio: Decimal,
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub timestamp: DateTime<Utc>,
+}
+
+///
+/// `result` of `/v5/market/time`.
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerTime {
+    #[serde(deserialize_with = "de::number")]
+    pub time_second: i64,
+    #[serde(deserialize_with = "de::number")]
+    pub time_nano: i128,
+}
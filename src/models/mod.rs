@@ -0,0 +1,25 @@
+pub mod account;
+pub mod asset;
+pub(crate) mod de;
+pub mod enums;
+pub mod market;
+pub mod order;
+pub mod position;
+pub mod user;
+
+use serde_derive::Deserialize;
+
+use enums::Category;
+
+///
+/// `result` of the list endpoints that are paged with a cursor.
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    #[serde(default)]
+    pub category: Option<Category>,
+    pub list: Vec<T>,
+    #[serde(default)]
+    pub next_page_cursor: String,
+}
@@ -0,0 +1,688 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{ser, Serialize, Serializer};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::errors::app_error::AppError;
+
+use super::{
+    de,
+    enums::{Category, OrderType, PositionIdx, Side, SmpType, TimeInForce, TpslMode, TriggerBy},
+};
+
+///
+/// Typed arguments of `/v5/order/create`. Build with `limit` or `market`, add optional
+/// fields, then `validate` catches combinations Bybit would reject.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/order/create-order
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceOrderRequest {
+    pub category: Category,
+    pub symbol: String,
+    pub side: Side,
+    pub order_type: OrderType,
+    pub qty: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_in_force: Option<TimeInForce>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_link_id: Option<String>,
+    /// Spot margin trading: 1 borrows, 0 does not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_leverage: Option<u8>,
+    /// 1 triggers when the price rises to `trigger_price`, 2 when it falls to it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_direction: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_price: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_by: Option<TriggerBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position_idx: Option<PositionIdx>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub take_profit: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_loss: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tp_trigger_by: Option<TriggerBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sl_trigger_by: Option<TriggerBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tpsl_mode: Option<TpslMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tp_limit_price: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sl_limit_price: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tp_order_type: Option<OrderType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sl_order_type: Option<OrderType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close_on_trigger: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smp_type: Option<SmpType>,
+}
+
+impl PlaceOrderRequest {
+    pub fn new(
+        category: Category,
+        symbol: &str,
+        side: Side,
+        order_type: OrderType,
+        qty: Decimal,
+    ) -> Self {
+        PlaceOrderRequest {
+            category,
+            symbol: symbol.to_owned(),
+            side,
+            order_type,
+            qty,
+            price: None,
+            time_in_force: None,
+            order_link_id: None,
+            is_leverage: None,
+            trigger_direction: None,
+            trigger_price: None,
+            trigger_by: None,
+            position_idx: None,
+            take_profit: None,
+            stop_loss: None,
+            tp_trigger_by: None,
+            sl_trigger_by: None,
+            tpsl_mode: None,
+            tp_limit_price: None,
+            sl_limit_price: None,
+            tp_order_type: None,
+            sl_order_type: None,
+            reduce_only: None,
+            close_on_trigger: None,
+            smp_type: None,
+        }
+    }
+
+    pub fn limit(
+        category: Category,
+        symbol: &str,
+        side: Side,
+        qty: Decimal,
+        price: Decimal,
+    ) -> Self {
+        Self::new(category, symbol, side, OrderType::Limit, qty).price(price)
+    }
+
+    pub fn market(category: Category, symbol: &str, side: Side, qty: Decimal) -> Self {
+        Self::new(category, symbol, side, OrderType::Market, qty)
+    }
+
+    pub fn price(mut self, price: Decimal) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    pub fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = Some(time_in_force);
+        self
+    }
+
+    ///
+    /// Client order id. It also makes the request safe to retry.
+    ///
+    pub fn order_link_id(mut self, order_link_id: &str) -> Self {
+        self.order_link_id = Some(order_link_id.to_owned());
+        self
+    }
+
+    pub fn is_leverage(mut self, is_leverage: bool) -> Self {
+        self.is_leverage = Some(is_leverage as u8);
+        self
+    }
+
+    ///
+    /// Makes the order conditional: it is placed once `trigger_by` crosses `trigger_price`
+    /// moving in the direction of `rises`.
+    ///
+    pub fn trigger(mut self, trigger_price: Decimal, rises: bool, trigger_by: TriggerBy) -> Self {
+        self.trigger_price = Some(trigger_price);
+        self.trigger_direction = Some(if rises { 1 } else { 2 });
+        self.trigger_by = Some(trigger_by);
+        self
+    }
+
+    pub fn position_idx(mut self, position_idx: PositionIdx) -> Self {
+        self.position_idx = Some(position_idx);
+        self
+    }
+
+    pub fn take_profit(mut self, take_profit: Decimal) -> Self {
+        self.take_profit = Some(take_profit);
+        self
+    }
+
+    pub fn stop_loss(mut self, stop_loss: Decimal) -> Self {
+        self.stop_loss = Some(stop_loss);
+        self
+    }
+
+    pub fn tp_trigger_by(mut self, trigger_by: TriggerBy) -> Self {
+        self.tp_trigger_by = Some(trigger_by);
+        self
+    }
+
+    pub fn sl_trigger_by(mut self, trigger_by: TriggerBy) -> Self {
+        self.sl_trigger_by = Some(trigger_by);
+        self
+    }
+
+    pub fn tpsl_mode(mut self, tpsl_mode: TpslMode) -> Self {
+        self.tpsl_mode = Some(tpsl_mode);
+        self
+    }
+
+    ///
+    /// Take profit as a limit order at `limit_price`. Requires `TpslMode::Partial`.
+    ///
+    pub fn tp_limit(mut self, limit_price: Decimal) -> Self {
+        self.tp_order_type = Some(OrderType::Limit);
+        self.tp_limit_price = Some(limit_price);
+        self
+    }
+
+    ///
+    /// Stop loss as a limit order at `limit_price`. Requires `TpslMode::Partial`.
+    ///
+    pub fn sl_limit(mut self, limit_price: Decimal) -> Self {
+        self.sl_order_type = Some(OrderType::Limit);
+        self.sl_limit_price = Some(limit_price);
+        self
+    }
+
+    pub fn reduce_only(mut self, reduce_only: bool) -> Self {
+        self.reduce_only = Some(reduce_only);
+        self
+    }
+
+    pub fn close_on_trigger(mut self, close_on_trigger: bool) -> Self {
+        self.close_on_trigger = Some(close_on_trigger);
+        self
+    }
+
+    pub fn smp_type(mut self, smp_type: SmpType) -> Self {
+        self.smp_type = Some(smp_type);
+        self
+    }
+
+    ///
+    /// Checks the field combinations Bybit would reject.
+    ///
+    pub fn validate(&self) -> Result<(), AppError> {
+        let invalid = |message: &str| Err(AppError::InvalidInput(message.to_owned()));
+
+        if self.symbol.is_empty() {
+            return invalid("symbol is required");
+        }
+        if self.qty <= Decimal::ZERO {
+            return invalid("qty must be positive");
+        }
+        match (self.order_type, self.price) {
+            (OrderType::Limit, None) => return invalid("Limit orders require a price"),
+            (_, Some(price)) if price <= Decimal::ZERO => return invalid("price must be positive"),
+            _ => {}
+        }
+        if self.time_in_force == Some(TimeInForce::PostOnly) && self.order_type != OrderType::Limit
+        {
+            return invalid("PostOnly requires a Limit order");
+        }
+        if (self.trigger_by.is_some() || self.trigger_direction.is_some())
+            && self.trigger_price.is_none()
+        {
+            return invalid("triggerBy and triggerDirection require triggerPrice");
+        }
+        if self.tp_trigger_by.is_some() && self.take_profit.is_none() {
+            return invalid("tpTriggerBy requires takeProfit");
+        }
+        if self.sl_trigger_by.is_some() && self.stop_loss.is_none() {
+            return invalid("slTriggerBy requires stopLoss");
+        }
+        let partial = self.tpsl_mode == Some(TpslMode::Partial);
+        if self.tp_order_type == Some(OrderType::Limit) && (!partial || self.take_profit.is_none())
+        {
+            return invalid("A Limit take profit requires takeProfit and TpslMode::Partial");
+        }
+        if self.sl_order_type == Some(OrderType::Limit) && (!partial || self.stop_loss.is_none()) {
+            return invalid("A Limit stop loss requires stopLoss and TpslMode::Partial");
+        }
+        match self.category {
+            Category::Spot => {
+                if self.reduce_only.is_some() || self.close_on_trigger.is_some() {
+                    return invalid("reduceOnly and closeOnTrigger are not supported for spot");
+                }
+                if self.position_idx.is_some() {
+                    return invalid("positionIdx is not supported for spot");
+                }
+            }
+            Category::Option => {
+                if self.order_link_id.is_none() {
+                    return invalid("Option orders require an orderLinkId");
+                }
+            }
+            Category::Linear | Category::Inverse => {
+                if self.is_leverage.is_some() {
+                    return invalid("isLeverage only applies to spot");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// The request as the string map taken by `Trade::place_order`.
+    ///
+    pub fn to_query(&self) -> Result<HashMap<String, String>, AppError> {
+        to_query(self)
+    }
+}
+
+///
+/// Typed arguments of `/v5/order/amend`. The order is picked by `order_id` or
+/// `order_link_id`; only the fields that are set are changed.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/order/amend-order
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmendOrderRequest {
+    pub category: Category,
+    pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_link_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qty: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_price: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_by: Option<TriggerBy>,
+    /// Zero cancels the take profit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub take_profit: Option<Decimal>,
+    /// Zero cancels the stop loss.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_loss: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tp_trigger_by: Option<TriggerBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sl_trigger_by: Option<TriggerBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tp_limit_price: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sl_limit_price: Option<Decimal>,
+}
+
+impl AmendOrderRequest {
+    pub fn by_order_id(category: Category, symbol: &str, order_id: &str) -> Self {
+        Self::new(category, symbol, Some(order_id.to_owned()), None)
+    }
+
+    pub fn by_order_link_id(category: Category, symbol: &str, order_link_id: &str) -> Self {
+        Self::new(category, symbol, None, Some(order_link_id.to_owned()))
+    }
+
+    fn new(
+        category: Category,
+        symbol: &str,
+        order_id: Option<String>,
+        order_link_id: Option<String>,
+    ) -> Self {
+        AmendOrderRequest {
+            category,
+            symbol: symbol.to_owned(),
+            order_id,
+            order_link_id,
+            qty: None,
+            price: None,
+            trigger_price: None,
+            trigger_by: None,
+            take_profit: None,
+            stop_loss: None,
+            tp_trigger_by: None,
+            sl_trigger_by: None,
+            tp_limit_price: None,
+            sl_limit_price: None,
+        }
+    }
+
+    pub fn qty(mut self, qty: Decimal) -> Self {
+        self.qty = Some(qty);
+        self
+    }
+
+    pub fn price(mut self, price: Decimal) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    pub fn trigger_price(mut self, trigger_price: Decimal) -> Self {
+        self.trigger_price = Some(trigger_price);
+        self
+    }
+
+    pub fn trigger_by(mut self, trigger_by: TriggerBy) -> Self {
+        self.trigger_by = Some(trigger_by);
+        self
+    }
+
+    pub fn take_profit(mut self, take_profit: Decimal) -> Self {
+        self.take_profit = Some(take_profit);
+        self
+    }
+
+    pub fn stop_loss(mut self, stop_loss: Decimal) -> Self {
+        self.stop_loss = Some(stop_loss);
+        self
+    }
+
+    pub fn tp_trigger_by(mut self, trigger_by: TriggerBy) -> Self {
+        self.tp_trigger_by = Some(trigger_by);
+        self
+    }
+
+    pub fn sl_trigger_by(mut self, trigger_by: TriggerBy) -> Self {
+        self.sl_trigger_by = Some(trigger_by);
+        self
+    }
+
+    pub fn tp_limit_price(mut self, limit_price: Decimal) -> Self {
+        self.tp_limit_price = Some(limit_price);
+        self
+    }
+
+    pub fn sl_limit_price(mut self, limit_price: Decimal) -> Self {
+        self.sl_limit_price = Some(limit_price);
+        self
+    }
+
+    ///
+    /// Checks the field combinations Bybit would reject.
+    ///
+    pub fn validate(&self) -> Result<(), AppError> {
+        let invalid = |message: &str| Err(AppError::InvalidInput(message.to_owned()));
+
+        validate_order_ref(&self.symbol, &self.order_id, &self.order_link_id)?;
+        if self.qty.map_or(false, |qty| qty <= Decimal::ZERO) {
+            return invalid("qty must be positive");
+        }
+        if self.price.map_or(false, |price| price <= Decimal::ZERO) {
+            return invalid("price must be positive");
+        }
+        if self.trigger_by.is_some() && self.trigger_price.is_none() {
+            return invalid("triggerBy requires triggerPrice");
+        }
+        if self.tp_trigger_by.is_some() && self.take_profit.is_none() {
+            return invalid("tpTriggerBy requires takeProfit");
+        }
+        if self.sl_trigger_by.is_some() && self.stop_loss.is_none() {
+            return invalid("slTriggerBy requires stopLoss");
+        }
+        Ok(())
+    }
+
+    ///
+    /// The request as the string map taken by `Trade::amend_order`.
+    ///
+    pub fn to_query(&self) -> Result<HashMap<String, String>, AppError> {
+        to_query(self)
+    }
+}
+
+///
+/// Typed arguments of `/v5/order/cancel`, picking the order by `order_id` or
+/// `order_link_id`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/order/cancel-order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrderRequest {
+    pub category: Category,
+    pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_link_id: Option<String>,
+}
+
+impl CancelOrderRequest {
+    pub fn by_order_id(category: Category, symbol: &str, order_id: &str) -> Self {
+        CancelOrderRequest {
+            category,
+            symbol: symbol.to_owned(),
+            order_id: Some(order_id.to_owned()),
+            order_link_id: None,
+        }
+    }
+
+    pub fn by_order_link_id(category: Category, symbol: &str, order_link_id: &str) -> Self {
+        CancelOrderRequest {
+            category,
+            symbol: symbol.to_owned(),
+            order_id: None,
+            order_link_id: Some(order_link_id.to_owned()),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), AppError> {
+        validate_order_ref(&self.symbol, &self.order_id, &self.order_link_id)
+    }
+
+    ///
+    /// The request as the string map taken by `Trade::cancel_order`.
+    ///
+    pub fn to_query(&self) -> Result<HashMap<String, String>, AppError> {
+        to_query(self)
+    }
+}
+
+///
+/// One order of a batch request. The batch sends the category once, so items must agree on it.
+///
+pub trait BatchItem: Serialize {
+    fn category(&self) -> Category;
+    fn validate(&self) -> Result<(), AppError>;
+}
+
+impl BatchItem for PlaceOrderRequest {
+    fn category(&self) -> Category {
+        self.category
+    }
+
+    fn validate(&self) -> Result<(), AppError> {
+        PlaceOrderRequest::validate(self)
+    }
+}
+
+impl BatchItem for AmendOrderRequest {
+    fn category(&self) -> Category {
+        self.category
+    }
+
+    fn validate(&self) -> Result<(), AppError> {
+        AmendOrderRequest::validate(self)
+    }
+}
+
+impl BatchItem for CancelOrderRequest {
+    fn category(&self) -> Category {
+        self.category
+    }
+
+    fn validate(&self) -> Result<(), AppError> {
+        CancelOrderRequest::validate(self)
+    }
+}
+
+///
+/// Body of the batch place, amend and cancel endpoints. The items are sent without
+/// their own `category`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/order/batch-place
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchOrderRequest<T: BatchItem> {
+    pub category: Category,
+    #[serde(serialize_with = "without_category")]
+    pub request: Vec<T>,
+}
+
+impl<T: BatchItem> BatchOrderRequest<T> {
+    ///
+    /// Batch of `request`, checking every item and that they share one category.
+    ///
+    pub fn new(request: Vec<T>) -> Result<Self, AppError> {
+        let category = match request.first() {
+            Some(item) => item.category(),
+            None => {
+                return Err(AppError::InvalidInput(
+                    "A batch needs at least one order".to_owned(),
+                ))
+            }
+        };
+        for item in &request {
+            if item.category() != category {
+                return Err(AppError::InvalidInput(format!(
+                    "A batch takes one category, got {} and {}",
+                    category,
+                    item.category()
+                )));
+            }
+            item.validate()?;
+        }
+        Ok(BatchOrderRequest { category, request })
+    }
+}
+
+fn without_category<T: Serialize, S: Serializer>(
+    items: &[T],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut values = Vec::with_capacity(items.len());
+    for item in items {
+        let mut value = serde_json::to_value(item).map_err(ser::Error::custom)?;
+        if let Value::Object(fields) = &mut value {
+            fields.remove("category");
+        }
+        values.push(value);
+    }
+    values.serialize(serializer)
+}
+
+///
+/// Outcome of one order of a batch: `result.list` joined with the `code` and `msg`
+/// of the same position in `retExtInfo.list`. A batch succeeds as a whole even when
+/// some of its orders are rejected, so check `is_success` on each.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/order/batch-place
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchOrderResult {
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub symbol: String,
+    /// Empty when the order was rejected.
+    #[serde(default)]
+    pub order_id: String,
+    #[serde(default)]
+    pub order_link_id: String,
+    /// Batch place only.
+    #[serde(default, deserialize_with = "de::timestamp_ms_opt")]
+    pub create_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub code: i64,
+    #[serde(default)]
+    pub msg: String,
+}
+
+impl BatchOrderResult {
+    pub fn is_success(&self) -> bool {
+        self.code == 0
+    }
+
+    ///
+    /// Joins the `list` of a batch reply's `result` with the `list` of its `retExtInfo`.
+    ///
+    pub fn from_reply(result: &Value, ret_ext_info: &Value) -> Result<Vec<Self>, AppError> {
+        let empty = Vec::new();
+        let statuses = ret_ext_info["list"].as_array().unwrap_or(&empty);
+        result["list"]
+            .as_array()
+            .unwrap_or(&empty)
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let mut item = item.clone();
+                if let (Value::Object(fields), Some(Value::Object(status))) =
+                    (&mut item, statuses.get(index))
+                {
+                    fields.extend(status.clone());
+                }
+                Ok(serde_json::from_value(item)?)
+            })
+            .collect()
+    }
+}
+
+fn validate_order_ref(
+    symbol: &str,
+    order_id: &Option<String>,
+    order_link_id: &Option<String>,
+) -> Result<(), AppError> {
+    if symbol.is_empty() {
+        return Err(AppError::InvalidInput("symbol is required".to_owned()));
+    }
+    if order_id.is_none() && order_link_id.is_none() {
+        return Err(AppError::InvalidInput(
+            "Either orderId or orderLinkId is required".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+fn to_query<T: Serialize>(request: &T) -> Result<HashMap<String, String>, AppError> {
+    let fields = match serde_json::to_value(request)? {
+        Value::Object(fields) => fields,
+        _ => unreachable!("order requests serialize to an object"),
+    };
+    Ok(fields
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(value) => value,
+                other => other.to_string(),
+            };
+            (key, value)
+        })
+        .collect())
+}
+
+///
+/// `result` of order create, amend and cancel.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderAck {
+    pub order_id: String,
+    #[serde(default)]
+    pub order_link_id: String,
+}
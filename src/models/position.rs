@@ -0,0 +1,186 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde_derive::Deserialize;
+
+use super::{
+    de,
+    enums::{PositionIdx, Side, TpslMode},
+};
+
+///
+/// One entry of `/v5/position/list`. A flat position has no `side` and a zero `size`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/position
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionInfo {
+    pub symbol: String,
+    pub position_idx: PositionIdx,
+    #[serde(default, deserialize_with = "de::enum_opt")]
+    pub side: Option<Side>,
+    pub size: Decimal,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub avg_price: Decimal,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub position_value: Decimal,
+    /// 0 cross margin, 1 isolated margin.
+    #[serde(default)]
+    pub trade_mode: u8,
+    /// `Normal`, `Liq` or `Adl`.
+    #[serde(default)]
+    pub position_status: String,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub leverage: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub mark_price: Decimal,
+    /// Empty when the position cannot be liquidated.
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub liq_price: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub bust_price: Option<Decimal>,
+    #[serde(
+        rename = "positionIM",
+        default,
+        deserialize_with = "de::decimal_or_zero"
+    )]
+    pub position_im: Decimal,
+    #[serde(
+        rename = "positionMM",
+        default,
+        deserialize_with = "de::decimal_or_zero"
+    )]
+    pub position_mm: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub position_balance: Decimal,
+    #[serde(default, deserialize_with = "de::enum_opt")]
+    pub tpsl_mode: Option<TpslMode>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub take_profit: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub stop_loss: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub trailing_stop: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub unrealised_pnl: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub cur_realised_pnl: Decimal,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub cum_realised_pnl: Decimal,
+    #[serde(default)]
+    pub risk_id: u64,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub risk_limit_value: Option<Decimal>,
+    /// 0 to 5, higher is more likely to be auto-deleveraged.
+    #[serde(default)]
+    pub adl_rank_indicator: u8,
+    #[serde(default)]
+    pub is_reduce_only: bool,
+    #[serde(default, deserialize_with = "de::timestamp_ms_opt")]
+    pub created_time: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "de::timestamp_ms_opt")]
+    pub updated_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub seq: i64,
+}
+
+impl PositionInfo {
+    pub fn is_open(&self) -> bool {
+        !self.size.is_zero()
+    }
+
+    ///
+    /// Size with the sign of the side: positive long, negative short.
+    ///
+    pub fn signed_size(&self) -> Decimal {
+        match self.side {
+            Some(Side::Sell) => -self.size,
+            _ => self.size,
+        }
+    }
+}
+
+///
+/// One fill of `/v5/execution/list`. `exec_type` is `Trade`, `Funding`,
+/// `AdlTrade`, `BustTrade` or `Settle`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/order/execution
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Execution {
+    pub symbol: String,
+    pub exec_id: String,
+    pub order_id: String,
+    #[serde(default)]
+    pub order_link_id: String,
+    pub side: Side,
+    /// `Market`, `Limit`, or `UNKNOWN` for funding and settlement.
+    pub order_type: String,
+    pub exec_type: String,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub order_price: Decimal,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub order_qty: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub leaves_qty: Decimal,
+    pub exec_price: Decimal,
+    pub exec_qty: Decimal,
+    pub exec_value: Decimal,
+    pub exec_fee: Decimal,
+    #[serde(deserialize_with = "de::decimal_or_zero")]
+    pub fee_rate: Decimal,
+    #[serde(default)]
+    pub is_maker: bool,
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub exec_time: DateTime<Utc>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub mark_price: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub index_price: Option<Decimal>,
+    /// Closed position size; empty for spot.
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub closed_size: Option<Decimal>,
+    /// Option fills only.
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub trade_iv: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub mark_iv: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::decimal_opt")]
+    pub underlying_price: Option<Decimal>,
+    #[serde(default)]
+    pub block_trade_id: String,
+    #[serde(default)]
+    pub seq: i64,
+}
+
+///
+/// One closed position of `/v5/position/closed-pnl`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/position/close-pnl
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosedPnl {
+    pub symbol: String,
+    pub order_id: String,
+    /// Side of the closing order.
+    pub side: Side,
+    pub qty: Decimal,
+    pub order_price: Decimal,
+    pub order_type: String,
+    pub exec_type: String,
+    pub closed_size: Decimal,
+    pub cum_entry_value: Decimal,
+    pub avg_entry_price: Decimal,
+    pub cum_exit_value: Decimal,
+    pub avg_exit_price: Decimal,
+    pub closed_pnl: Decimal,
+    #[serde(deserialize_with = "de::number")]
+    pub fill_count: u64,
+    pub leverage: Decimal,
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub created_time: DateTime<Utc>,
+    #[serde(deserialize_with = "de::timestamp_ms")]
+    pub updated_time: DateTime<Utc>,
+}
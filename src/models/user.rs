@@ -0,0 +1,313 @@
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+
+use super::de;
+
+///
+/// Typed arguments of `/v5/user/create-sub-member`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/user/create-subuid
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSubUidRequest {
+    /// 6-16 characters with both letters and numbers, not used by an existing or deleted sub UID.
+    pub username: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// 1 normal sub account, 6 custodial sub account.
+    pub member_type: u8,
+    /// 1 turns quick login on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub switch: Option<u8>,
+    /// Create the sub account as a unified trading account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_uta: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl CreateSubUidRequest {
+    pub fn new(username: &str, member_type: u8) -> Self {
+        CreateSubUidRequest {
+            username: username.to_owned(),
+            password: None,
+            member_type,
+            switch: None,
+            is_uta: None,
+            note: None,
+        }
+    }
+
+    pub fn password(mut self, password: &str) -> Self {
+        self.password = Some(password.to_owned());
+        self
+    }
+
+    pub fn quick_login(mut self, enabled: bool) -> Self {
+        self.switch = Some(enabled as u8);
+        self
+    }
+
+    pub fn is_uta(mut self, is_uta: bool) -> Self {
+        self.is_uta = Some(is_uta);
+        self
+    }
+
+    pub fn note(mut self, note: &str) -> Self {
+        self.note = Some(note.to_owned());
+        self
+    }
+}
+
+///
+/// Permissions of an API key, by group. A group that is `None` is not granted.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ApiPermissions {
+    /// `Order`, `Position`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_trade: Option<Vec<String>>,
+    /// `SpotTrade`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spot: Option<Vec<String>>,
+    /// `AccountTransfer`, `SubMemberTransferList`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<Vec<String>>,
+    /// `OptionsTrade`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<String>>,
+    /// `DerivativesTrade`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derivatives: Option<Vec<String>>,
+    /// `ExchangeHistory`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exchange: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copy_trading: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_trade: Option<Vec<String>>,
+    #[serde(rename = "NFT", default, skip_serializing_if = "Option::is_none")]
+    pub nft: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub affiliate: Option<Vec<String>>,
+}
+
+///
+/// Typed arguments of `/v5/user/create-sub-api`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/user/create-subuid-apikey
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSubApiKeyRequest {
+    pub subuid: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// 0 read and write, 1 read only.
+    pub read_only: u8,
+    /// Comma separated IPs the key is bound to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ips: Option<String>,
+    pub permissions: ApiPermissions,
+}
+
+impl CreateSubApiKeyRequest {
+    pub fn new(subuid: u64, read_only: bool, permissions: ApiPermissions) -> Self {
+        CreateSubApiKeyRequest {
+            subuid,
+            note: None,
+            read_only: read_only as u8,
+            ips: None,
+            permissions,
+        }
+    }
+
+    pub fn note(mut self, note: &str) -> Self {
+        self.note = Some(note.to_owned());
+        self
+    }
+
+    pub fn ips(mut self, ips: &[&str]) -> Self {
+        self.ips = Some(ips.join(","));
+        self
+    }
+}
+
+///
+/// Typed arguments of `/v5/user/frozen-sub-member`.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/user/froze-subuid
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FreezeSubUidRequest {
+    pub subuid: u64,
+    /// 0 unfreezes, 1 freezes.
+    pub frozen: u8,
+}
+
+impl FreezeSubUidRequest {
+    pub fn new(subuid: u64, frozen: bool) -> Self {
+        FreezeSubUidRequest {
+            subuid,
+            frozen: frozen as u8,
+        }
+    }
+}
+
+///
+/// Typed arguments of `/v5/user/del-submember`. The sub account must hold no assets.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/user/rm-subuid
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteSubUidRequest {
+    pub sub_member_id: String,
+}
+
+impl DeleteSubUidRequest {
+    pub fn new(sub_member_id: u64) -> Self {
+        DeleteSubUidRequest {
+            sub_member_id: sub_member_id.to_string(),
+        }
+    }
+}
+
+///
+/// A sub account, as created by `/v5/user/create-sub-member` and listed by
+/// `/v5/user/query-sub-members`.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubMember {
+    #[serde(deserialize_with = "de::number")]
+    pub uid: u64,
+    pub username: String,
+    pub member_type: u8,
+    /// 1 normal, 2 login banned, 4 frozen.
+    pub status: u8,
+    /// 1 classic, 3 unified trading, 5 UTA 2.0. Not sent on creation.
+    #[serde(default)]
+    pub account_mode: Option<u8>,
+    #[serde(default)]
+    pub remark: String,
+}
+
+///
+/// `result` of `/v5/user/query-sub-members`.
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubMembers {
+    pub sub_members: Vec<SubMember>,
+}
+
+///
+/// An API key, as returned by `/v5/user/query-api`, `/v5/user/sub-apikeys` and
+/// `/v5/user/create-sub-api`. `secret` is only readable right after creation.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/user/apikey-info
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyInfo {
+    #[serde(deserialize_with = "de::number")]
+    pub id: u64,
+    #[serde(default)]
+    pub note: String,
+    pub api_key: String,
+    #[serde(deserialize_with = "de::flag")]
+    pub read_only: bool,
+    #[serde(default)]
+    pub secret: String,
+    #[serde(default)]
+    pub permissions: ApiPermissions,
+    #[serde(default)]
+    pub ips: Vec<String>,
+    /// 1 personal, 2 connected to a third party application.
+    #[serde(rename = "type", default)]
+    pub kind: u8,
+    /// Days until the key expires, for keys without IP binding.
+    #[serde(default)]
+    pub deadline_day: i64,
+    /// RFC 3339, empty for keys that do not expire.
+    #[serde(default)]
+    pub expired_at: String,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(rename = "userID", default, deserialize_with = "de::number_opt")]
+    pub user_id: Option<u64>,
+    #[serde(default)]
+    pub is_master: bool,
+}
+
+///
+/// `result` of `/v5/user/sub-apikeys`, which puts its items under `result`.
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubApiKeys {
+    #[serde(rename = "result")]
+    pub list: Vec<ApiKeyInfo>,
+    #[serde(default)]
+    pub next_page_cursor: String,
+}
+
+///
+/// Wallets of one UID in `/v5/user/get-member-type`.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberWallets {
+    #[serde(deserialize_with = "de::number")]
+    pub uid: u64,
+    /// `SPOT`, `CONTRACT`, `FUND`, `OPTION`, `UNIFIED`.
+    pub account_type: Vec<String>,
+}
+
+///
+/// `result` of `/v5/user/get-member-type`.
+///
+#[derive(Debug, Clone, Deserialize)]
+pub struct MemberWalletTypes {
+    pub accounts: Vec<MemberWallets>,
+}
+
+///
+/// `result` of `/v5/user/aff-customer-info`: trading and deposit volumes of a
+/// customer invited by the affiliate calling it.
+///
+/// Additional information:
+///     https://bybit-exchange.github.io/docs/v5/user/affiliate-info
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AffiliateCustomerInfo {
+    #[serde(deserialize_with = "de::number")]
+    pub uid: u64,
+    #[serde(default)]
+    pub vip_level: String,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub taker_vol30_day: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub maker_vol30_day: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub trade_vol30_day: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub deposit_amount30_day: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub taker_vol365_day: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub maker_vol365_day: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub trade_vol365_day: Decimal,
+    #[serde(default, deserialize_with = "de::decimal_or_zero")]
+    pub deposit_amount365_day: Decimal,
+    /// Wallet balance bucket, 1 to 4.
+    #[serde(default)]
+    pub total_wallet_balance: String,
+    #[serde(rename = "KycLevel", default)]
+    pub kyc_level: u8,
+}
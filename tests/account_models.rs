@@ -0,0 +1,181 @@
+mod common;
+
+use std::{collections::HashMap, sync::Arc};
+
+use bybit_rs::{
+    bybit::{
+        account::{Account, AccountHTTP},
+        http_manager::HttpManager,
+        position::{Position, PositionHTTP},
+    },
+    models::{
+        account::TransactionLogEntry,
+        enums::{PositionIdx, Side},
+        position::{ClosedPnl, Execution},
+        Page,
+    },
+};
+use mockito::{Matcher, Server, ServerGuard};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use common::{dec, envelope, manager};
+
+async fn serve(path: &str, result: Value) -> (ServerGuard, Arc<HttpManager>) {
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", path)
+        .match_query(Matcher::Any)
+        .with_body(envelope(result))
+        .create_async()
+        .await;
+    let manager = manager(&server);
+    (server, manager)
+}
+
+#[tokio::test]
+async fn wallet_balance_keeps_exact_decimals() {
+    let (_server, manager) = serve(
+        "/v5/account/wallet-balance",
+        json!({"list": [{
+            "totalEquity": "3.31216591", "accountIMRate": "0", "totalMarginBalance": "3.00326056",
+            "totalInitialMargin": "0", "accountType": "UNIFIED", "totalAvailableBalance": "3.00326056",
+            "accountMMRate": "0", "totalPerpUPL": "0", "totalWalletBalance": "3.00326056",
+            "accountLTV": "0", "totalMaintenanceMargin": "0",
+            "coin": [{
+                "availableToBorrow": "3", "bonus": "0", "accruedInterest": "0", "availableToWithdraw": "0",
+                "totalOrderIM": "0", "equity": "0.10000000000000000001", "totalPositionMM": "0",
+                "usdValue": "2.9", "unrealisedPnl": "0", "collateralSwitch": true, "borrowAmount": "0.0",
+                "totalPositionIM": "0", "walletBalance": "0.1", "cumRealisedPnl": "", "locked": "0",
+                "marginCollateral": true, "coin": "BTC"
+            }]
+        }]}),
+    )
+    .await;
+
+    let account = AccountHTTP::new(manager);
+    let balances = account
+        .get_wallet_balance_typed(HashMap::new())
+        .await
+        .unwrap();
+    assert_eq!(balances[0].total_equity, Some(dec("3.31216591")));
+    let btc = balances[0].coin("BTC").unwrap();
+    assert_eq!(btc.equity, dec("0.10000000000000000001"));
+    assert_eq!(
+        btc.equity - btc.wallet_balance,
+        dec("0.00000000000000000001")
+    );
+    assert_eq!(btc.cum_realised_pnl, Decimal::ZERO);
+    assert!(balances[0].coin("ETH").is_none());
+}
+
+#[tokio::test]
+async fn json_numbers_keep_every_digit() {
+    // numbers, not strings, with more significant digits than an f64 holds
+    let result: Value = serde_json::from_str(
+        r#"{"list": [{
+            "totalEquity": 12345678901234567.89, "accountType": "UNIFIED",
+            "coin": [{"coin": "BTC", "equity": 0.10000000000000000001, "walletBalance": 0.1,
+                      "usdValue": 2.9, "unrealisedPnl": 0, "cumRealisedPnl": 0}]
+        }]}"#,
+    )
+    .unwrap();
+    let (_server, manager) = serve("/v5/account/wallet-balance", result).await;
+
+    let balances = AccountHTTP::new(manager)
+        .get_wallet_balance_typed(HashMap::new())
+        .await
+        .unwrap();
+    assert_eq!(balances[0].total_equity, Some(dec("12345678901234567.89")));
+    let btc = balances[0].coin("BTC").unwrap();
+    assert_eq!(btc.equity, dec("0.10000000000000000001"));
+    assert_eq!(
+        btc.equity - btc.wallet_balance,
+        dec("0.00000000000000000001")
+    );
+}
+
+#[tokio::test]
+async fn positions_parse_open_and_flat_entries() {
+    let position = |side: &str, size: &str, liq_price: &str| {
+        json!({
+            "positionIdx": 0, "riskId": 1, "riskLimitValue": "150", "symbol": "BTCUSD", "side": side,
+            "size": size, "avgPrice": "16800", "positionValue": "0.00176", "tradeMode": 0,
+            "positionStatus": "Normal", "autoAddMargin": 1, "adlRankIndicator": 2, "leverage": "1",
+            "positionBalance": "0.00139", "markPrice": "16817.22", "liqPrice": liq_price,
+            "bustPrice": "", "positionMM": "0.0000015", "positionIM": "0.00001", "tpslMode": "Full",
+            "takeProfit": "0.00", "stopLoss": "0.00", "trailingStop": "0.00", "unrealisedPnl": "-0.00000012",
+            "curRealisedPnl": "-0.00000003", "cumRealisedPnl": "-0.00000151", "seq": 4688002127i64,
+            "isReduceOnly": false, "createdTime": "1676538056258", "updatedTime": "1697673600012"
+        })
+    };
+    let (_server, manager) = serve(
+        "/v5/position/list",
+        json!({"category": "inverse", "nextPageCursor": "updateAt%3D1672279322668",
+               "list": [position("Sell", "29.5", "20000"), position("None", "0", "")]}),
+    )
+    .await;
+
+    let positions = PositionHTTP::new(manager)
+        .get_position_typed(HashMap::new())
+        .await
+        .unwrap();
+    assert_eq!(positions.next_page_cursor, "updateAt%3D1672279322668");
+    let (open, flat) = (&positions.list[0], &positions.list[1]);
+    assert_eq!(open.position_idx, PositionIdx::OneWay);
+    assert_eq!(open.signed_size(), dec("-29.5"));
+    assert_eq!(open.liq_price, Some(dec("20000")));
+    assert_eq!(open.unrealised_pnl, dec("-0.00000012"));
+    assert!(!flat.is_open());
+    assert_eq!(flat.side, None);
+    assert_eq!(flat.liq_price, None);
+}
+
+#[test]
+fn executions_closed_pnl_and_transaction_log_parse() {
+    let executions: Page<Execution> = serde_json::from_value(json!({
+        "category": "linear",
+        "list": [{
+            "symbol": "ETHPERP", "orderType": "Market", "underlyingPrice": "", "orderLinkId": "",
+            "side": "Buy", "indexPrice": "", "orderId": "8c065341-7b52-4ca9-ac2c-37e31ac55c94",
+            "stopOrderType": "UNKNOWN", "leavesQty": "0", "execTime": "1672282722429",
+            "isMaker": false, "execFee": "0.071409", "feeRate": "0.0006", "execId": "e0cbe81d",
+            "tradeIv": "", "blockTradeId": "", "markPrice": "1183.54", "execPrice": "1190.15",
+            "markIv": "", "orderQty": "0.1", "orderPrice": "1236.9", "execValue": "119.015",
+            "execType": "Trade", "execQty": "0.1", "closedSize": ""
+        }]
+    }))
+    .unwrap();
+    let fill = &executions.list[0];
+    assert_eq!(fill.exec_price * fill.exec_qty, fill.exec_value);
+    assert_eq!(fill.trade_iv, None);
+
+    let closed: Page<ClosedPnl> = serde_json::from_value(json!({
+        "category": "linear",
+        "list": [{
+            "symbol": "ETHPERP", "orderType": "Market", "leverage": "3", "updatedTime": "1672214887236",
+            "side": "Sell", "orderId": "5a373bfe", "closedPnl": "-47.4065323", "avgEntryPrice": "1194.97516667",
+            "qty": "3", "cumEntryValue": "3584.9255", "createdTime": "1672214887231", "orderPrice": "1122.95",
+            "closedSize": "3", "avgExitPrice": "1180.59833333", "execType": "Trade", "fillCount": "4",
+            "cumExitValue": "3541.795"
+        }]
+    }))
+    .unwrap();
+    assert_eq!(closed.list[0].side, Side::Sell);
+    assert_eq!(closed.list[0].fill_count, 4);
+
+    let log: Page<TransactionLogEntry> = serde_json::from_value(json!({
+        "list": [{
+            "id": "592324_XRPUSDT_161440249321", "symbol": "XRPUSDT", "category": "linear", "side": "Buy",
+            "transactionTime": "1682581500000", "type": "TRADE", "qty": "50", "size": "100",
+            "currency": "USDC", "tradePrice": "0.3915", "funding": "", "fee": "0.01174500",
+            "cashFlow": "-19.575", "change": "-19.5867450", "cashBalance": "1130.4132550",
+            "feeRate": "0.0006", "bonusChange": "", "tradeId": "1", "orderId": "2", "orderLinkId": ""
+        }],
+        "nextPageCursor": "21963%3A1%2C14954%3A1"
+    }))
+    .unwrap();
+    let trade = &log.list[0];
+    assert_eq!(trade.funding, None);
+    assert_eq!(trade.cash_flow - trade.fee, trade.change);
+}
@@ -0,0 +1,53 @@
+use bybit_rs::{bybit::response::ApiResponse, errors::app_error::AppError};
+use serde_json::json;
+
+#[test]
+fn success_returns_result() {
+    let envelope = ApiResponse::from_value(json!({
+        "retCode": 0,
+        "retMsg": "OK",
+        "result": {"timeSecond": "1688639403"},
+        "retExtInfo": {},
+        "time": 1688639403423u64,
+    }))
+    .unwrap();
+
+    assert_eq!(envelope.time, 1688639403423);
+    let result = envelope.into_result().unwrap();
+    assert_eq!(result["timeSecond"], "1688639403");
+}
+
+#[test]
+fn non_zero_ret_code_maps_to_error() {
+    let rejected = |ret_code: i64| {
+        ApiResponse::from_value(json!({
+            "retCode": ret_code,
+            "retMsg": "rejected",
+            "result": {},
+            "retExtInfo": {"list": []},
+            "time": 1688639403423u64,
+        }))
+        .unwrap()
+        .into_result()
+        .unwrap_err()
+    };
+
+    assert!(matches!(rejected(110007), AppError::InsufficientBalance { .. }));
+    assert!(matches!(rejected(10006), AppError::RateLimited { .. }));
+    assert!(matches!(rejected(10004), AppError::InvalidSignature { .. }));
+    assert!(matches!(rejected(10002), AppError::TimestampOutOfRecvWindow { .. }));
+    assert!(matches!(rejected(110001), AppError::OrderNotFound { .. }));
+
+    match rejected(10001) {
+        AppError::Api {
+            ret_code,
+            ret_msg,
+            ret_ext_info,
+        } => {
+            assert_eq!(ret_code, 10001);
+            assert_eq!(ret_msg, "rejected");
+            assert_eq!(ret_ext_info, json!({"list": []}));
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+}
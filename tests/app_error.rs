@@ -0,0 +1,60 @@
+use bybit_rs::errors::app_error::AppError;
+use serde_json::json;
+
+fn variant(error: &AppError) -> &'static str {
+    match error {
+        AppError::TimestampOutOfRecvWindow { .. } => "TimestampOutOfRecvWindow",
+        AppError::InvalidSignature { .. } => "InvalidSignature",
+        AppError::RateLimited { .. } => "RateLimited",
+        AppError::InsufficientBalance { .. } => "InsufficientBalance",
+        AppError::OrderNotFound { .. } => "OrderNotFound",
+        AppError::Api { .. } => "Api",
+        _ => "other",
+    }
+}
+
+#[test]
+fn ret_codes_map_to_their_variants() {
+    let table = [
+        (10002, "TimestampOutOfRecvWindow"),
+        (10004, "InvalidSignature"),
+        (10006, "RateLimited"),
+        (10018, "RateLimited"),
+        (110004, "InsufficientBalance"),
+        (110007, "InsufficientBalance"),
+        (110012, "InsufficientBalance"),
+        (170131, "InsufficientBalance"),
+        (110001, "OrderNotFound"),
+        (170213, "OrderNotFound"),
+        (10001, "Api"),
+    ];
+
+    for (ret_code, expected) in table {
+        let error = AppError::from_ret_code(ret_code, "message".to_owned(), json!({}));
+        assert_eq!(variant(&error), expected, "retCode {}", ret_code);
+        assert_eq!(error.ret_code(), Some(ret_code));
+        assert!(error.to_string().contains("message"));
+    }
+}
+
+#[test]
+fn unknown_ret_codes_keep_the_ext_info() {
+    let error = AppError::from_ret_code(
+        10001,
+        "params error".to_owned(),
+        json!({"list": [{"code": 1}]}),
+    );
+    match error {
+        AppError::Api {
+            ret_code,
+            ret_msg,
+            ret_ext_info,
+        } => {
+            assert_eq!(ret_code, 10001);
+            assert_eq!(ret_msg, "params error");
+            assert_eq!(ret_ext_info["list"][0]["code"], 1);
+        }
+        other => panic!("unexpected variant {:?}", other),
+    }
+    assert_eq!(AppError::InvalidInput("x".to_owned()).ret_code(), None);
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use std::collections::HashMap;
+
+use bybit_rs::{
+    bybit::trade::{Trade, TradeHTTP},
+    errors::app_error::AppError,
+    models::{
+        enums::{Category, Side},
+        order::{AmendOrderRequest, BatchOrderRequest, CancelOrderRequest, PlaceOrderRequest},
+    },
+};
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+use common::{dec, envelope, manager};
+
+#[test]
+fn batch_sends_the_category_once() {
+    let batch = BatchOrderRequest::new(vec![
+        PlaceOrderRequest::limit(
+            Category::Linear,
+            "BTCUSDT",
+            Side::Buy,
+            dec("0.01"),
+            dec("25000"),
+        ),
+        PlaceOrderRequest::market(Category::Linear, "ETHUSDT", Side::Sell, dec("0.1")),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&batch).unwrap(),
+        json!({
+            "category": "linear",
+            "request": [
+                {"symbol": "BTCUSDT", "side": "Buy", "orderType": "Limit", "qty": "0.01", "price": "25000"},
+                {"symbol": "ETHUSDT", "side": "Sell", "orderType": "Market", "qty": "0.1"},
+            ],
+        })
+    );
+}
+
+#[test]
+fn rejects_mixed_categories_and_invalid_items() {
+    let mixed = BatchOrderRequest::new(vec![
+        CancelOrderRequest::by_order_id(Category::Linear, "BTCUSDT", "1"),
+        CancelOrderRequest::by_order_id(Category::Spot, "BTCUSDT", "2"),
+    ]);
+    assert!(matches!(mixed, Err(AppError::InvalidInput(_))));
+
+    let empty = BatchOrderRequest::<CancelOrderRequest>::new(Vec::new());
+    assert!(matches!(empty, Err(AppError::InvalidInput(_))));
+
+    let mut no_id = AmendOrderRequest::by_order_id(Category::Linear, "BTCUSDT", "1").qty(dec("1"));
+    no_id.order_id = None;
+    let invalid = BatchOrderRequest::new(vec![no_id]);
+    assert!(matches!(invalid, Err(AppError::InvalidInput(_))));
+}
+
+#[tokio::test]
+async fn batch_cancel_uses_the_batch_route_and_reports_each_order() {
+    let mut server = Server::new_async().await;
+    let cancel_all = server
+        .mock("POST", "/v5/order/cancel-all")
+        .expect(0)
+        .create_async()
+        .await;
+    let cancel_batch = server
+        .mock("POST", "/v5/order/cancel-batch")
+        .match_body(Matcher::PartialJson(json!({
+            "category": "linear",
+            "request": [{"symbol": "BTCUSDT", "orderId": "1"}, {"symbol": "BTCUSDT", "orderLinkId": "gone"}],
+        })))
+        .with_body(
+            json!({
+                "retCode": 0,
+                "retMsg": "OK",
+                "result": {"list": [
+                    {"category": "linear", "symbol": "BTCUSDT", "orderId": "1", "orderLinkId": ""},
+                    {"category": "linear", "symbol": "BTCUSDT", "orderId": "", "orderLinkId": "gone"},
+                ]},
+                "retExtInfo": {"list": [
+                    {"code": 0, "msg": "OK"},
+                    {"code": 110001, "msg": "Order does not exist"},
+                ]},
+                "time": 0,
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let trade = TradeHTTP::new(manager(&server));
+    let batch = BatchOrderRequest::new(vec![
+        CancelOrderRequest::by_order_id(Category::Linear, "BTCUSDT", "1"),
+        CancelOrderRequest::by_order_link_id(Category::Linear, "BTCUSDT", "gone"),
+    ])
+    .unwrap();
+    let results = trade.batch_cancel_order(batch).await.unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_success());
+    assert_eq!(results[0].order_id, "1");
+    assert!(!results[1].is_success());
+    assert_eq!(results[1].code, 110001);
+    assert_eq!(results[1].order_link_id, "gone");
+    cancel_batch.assert_async().await;
+    cancel_all.assert_async().await;
+}
+
+#[tokio::test]
+#[allow(deprecated)]
+async fn old_batch_names_still_resolve() {
+    let _batch: bybit_rs::bybit::trade::BatchOrderRequest<CancelOrderRequest> =
+        BatchOrderRequest::new(vec![CancelOrderRequest::by_order_id(
+            Category::Option,
+            "BTC-29DEC23-40000-C",
+            "1",
+        )])
+        .unwrap();
+
+    let mut server = Server::new_async().await;
+    let cancel_batch = server
+        .mock("POST", "/v5/order/cancel-batch")
+        .match_body(Matcher::Json(json!({"category": "option"})))
+        .with_body(envelope(json!({"list": []})))
+        .create_async()
+        .await;
+
+    let trade = TradeHTTP::new(manager(&server));
+    let mut query = HashMap::new();
+    query.insert("category".to_owned(), "option".to_owned());
+    trade.cancel_batch_order(query).await.unwrap();
+    cancel_batch.assert_async().await;
+}
@@ -0,0 +1,83 @@
+#![allow(dead_code)]
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use bybit_rs::bybit::{http_manager::HttpManager, websocket_stream::ReconnectPolicy};
+use futures::{SinkExt, StreamExt};
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{accept_async, tungstenite::Message, WebSocketStream};
+
+pub type ServerSocket = WebSocketStream<TcpStream>;
+
+pub fn fast_policy() -> ReconnectPolicy {
+    ReconnectPolicy {
+        initial_backoff: Duration::from_millis(10),
+        max_backoff: Duration::from_millis(50),
+        max_attempts: Some(5),
+    }
+}
+
+pub async fn accept(listener: &TcpListener) -> ServerSocket {
+    let (tcp, _) = listener.accept().await.unwrap();
+    accept_async(tcp).await.unwrap()
+}
+
+pub async fn read_op(socket: &mut ServerSocket) -> Value {
+    loop {
+        match socket.next().await.unwrap().unwrap() {
+            Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+            _ => continue,
+        }
+    }
+}
+
+pub async fn push(socket: &mut ServerSocket, value: Value) {
+    socket.send(Message::Text(value.to_string())).await.unwrap();
+}
+
+pub async fn next_event<T>(events: &mut futures::channel::mpsc::UnboundedReceiver<T>) -> T {
+    tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .expect("timed out waiting for an event")
+        .expect("event stream ended")
+}
+
+pub fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+pub fn manager(server: &Server) -> Arc<HttpManager> {
+    let manager = HttpManager::builder("key".to_owned(), "secret".to_owned())
+        .base_url(&server.url())
+        .build()
+        .unwrap();
+    Arc::new(manager)
+}
+
+///
+/// A successful REST reply wrapping `result`.
+///
+pub fn envelope(result: Value) -> String {
+    json!({"retCode": 0, "retMsg": "OK", "result": result, "retExtInfo": {}, "time": 0}).to_string()
+}
+
+///
+/// Mocks `method path` with a body matching `body`, answering with `result`.
+///
+pub async fn reply(
+    server: &mut ServerGuard,
+    method: &str,
+    path: &str,
+    body: Matcher,
+    result: Value,
+) -> Mock {
+    server
+        .mock(method, path)
+        .match_query(Matcher::Any)
+        .match_body(body)
+        .with_body(envelope(result))
+        .create_async()
+        .await
+}
@@ -0,0 +1,272 @@
+mod common;
+
+use std::collections::HashMap;
+
+use bybit_rs::{
+    bybit::asset::{Asset, AssetHTTP},
+    errors::app_error::AppError,
+    models::{
+        asset::{Conversion, ConvertQuoteRequest},
+        enums::{ConvertAccount, ConvertStatus},
+    },
+};
+use chrono::{Duration, Utc};
+use mockito::{Matcher, Server};
+use serde_json::{json, Value};
+
+use common::{dec, envelope, manager, reply};
+
+fn quote(expired_time: i64) -> Value {
+    json!({
+        "quoteTxId": "10100108106409343501030232064",
+        "exchangeRate": "0.000015",
+        "fromCoin": "ETH",
+        "fromCoinType": "crypto",
+        "toCoin": "BTC",
+        "toCoinType": "crypto",
+        "fromAmount": "0.1",
+        "toAmount": "0.0000015",
+        "expiredTime": expired_time.to_string(),
+        "requestId": "",
+        "extTaxAndFee": [],
+    })
+}
+
+#[tokio::test]
+async fn convert_confirms_a_live_quote() {
+    let mut server = Server::new_async().await;
+    let expires = (Utc::now() + Duration::seconds(15)).timestamp_millis();
+    let apply = reply(
+        &mut server,
+        "POST",
+        "/v5/asset/exchange/quote-apply",
+        Matcher::Json(json!({
+            "accountType": "eb_convert_funding",
+            "fromCoin": "ETH",
+            "toCoin": "BTC",
+            "requestCoin": "ETH",
+            "requestAmount": "0.1",
+        })),
+        quote(expires),
+    )
+    .await;
+    let execute = reply(
+        &mut server,
+        "POST",
+        "/v5/asset/exchange/convert-execute",
+        Matcher::Json(json!({"quoteTxId": "10100108106409343501030232064"})),
+        json!({"quoteTxId": "10100108106409343501030232064", "exchangeStatus": "processing"}),
+    )
+    .await;
+
+    let asset = AssetHTTP::new(manager(&server));
+    let request = ConvertQuoteRequest::sell(ConvertAccount::Funding, "ETH", "BTC", dec("0.1"));
+    let conversion = asset.convert(&request).await.unwrap();
+
+    assert_eq!(conversion.quote.to_amount, dec("0.0000015"));
+    assert_eq!(conversion.quote.expired_time.timestamp_millis(), expires);
+    assert_eq!(conversion.exchange_status, ConvertStatus::Processing);
+    apply.assert_async().await;
+    execute.assert_async().await;
+}
+
+#[tokio::test]
+async fn convert_does_not_confirm_an_expired_quote() {
+    let mut server = Server::new_async().await;
+    let expired = (Utc::now() - Duration::seconds(1)).timestamp_millis();
+    let _apply = reply(
+        &mut server,
+        "POST",
+        "/v5/asset/exchange/quote-apply",
+        Matcher::PartialJson(json!({"requestCoin": "BTC"})),
+        quote(expired),
+    )
+    .await;
+    let execute = server
+        .mock("POST", "/v5/asset/exchange/convert-execute")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let asset = AssetHTTP::new(manager(&server));
+    let request = ConvertQuoteRequest::buy(ConvertAccount::Funding, "ETH", "BTC", dec("0.0000015"));
+    let result = asset.convert(&request).await;
+
+    match result {
+        Err(AppError::QuoteExpired {
+            quote_tx_id,
+            expired_time,
+        }) => {
+            assert_eq!(quote_tx_id, "10100108106409343501030232064");
+            assert_eq!(expired_time.timestamp_millis(), expired);
+        }
+        other => panic!("expected an expired quote, got {:?}", other),
+    }
+    execute.assert_async().await;
+}
+
+///
+/// Runs `convert` against a server whose clock is `skew_ms` ahead of the local one, for a
+/// quote expiring `expires_in_ms` after server now. Returns the result and whether the
+/// quote was confirmed.
+///
+async fn convert_with_skew(
+    skew_ms: i64,
+    expires_in_ms: i64,
+) -> (Result<Conversion, AppError>, bool) {
+    let mut server = Server::new_async().await;
+    let local = Utc::now().timestamp_millis();
+    let _apply = reply(
+        &mut server,
+        "POST",
+        "/v5/asset/exchange/quote-apply",
+        Matcher::Any,
+        quote(local + skew_ms + expires_in_ms),
+    )
+    .await;
+    let execute = reply(
+        &mut server,
+        "POST",
+        "/v5/asset/exchange/convert-execute",
+        Matcher::Any,
+        json!({"quoteTxId": "10100108106409343501030232064", "exchangeStatus": "processing"}),
+    )
+    .await;
+
+    let manager = manager(&server);
+    manager
+        .time_sync()
+        .record(local as u128, local as u128, (local + skew_ms) as u128);
+    let request = ConvertQuoteRequest::sell(ConvertAccount::Funding, "ETH", "BTC", dec("0.1"));
+    let result = AssetHTTP::new(manager).convert(&request).await;
+    (result, execute.matched_async().await)
+}
+
+#[tokio::test]
+async fn convert_checks_expiry_by_the_server_clock() {
+    // live by the local clock, expired by the server's
+    let (result, confirmed) = convert_with_skew(60_000, -45_000).await;
+    assert!(matches!(result, Err(AppError::QuoteExpired { .. })));
+    assert!(!confirmed);
+
+    // expired by the local clock, live by the server's
+    let (result, confirmed) = convert_with_skew(-60_000, 30_000).await;
+    assert_eq!(result.unwrap().exchange_status, ConvertStatus::Processing);
+    assert!(confirmed);
+
+    // too close to expiry for the confirmation to arrive in time
+    let (result, confirmed) = convert_with_skew(0, 500).await;
+    assert!(matches!(result, Err(AppError::QuoteExpired { .. })));
+    assert!(!confirmed);
+}
+
+#[tokio::test]
+async fn convert_confirmation_is_not_retried() {
+    let mut server = Server::new_async().await;
+    let expires = (Utc::now() + Duration::seconds(15)).timestamp_millis();
+    let _apply = reply(
+        &mut server,
+        "POST",
+        "/v5/asset/exchange/quote-apply",
+        Matcher::Any,
+        quote(expires),
+    )
+    .await;
+    let execute = server
+        .mock("POST", "/v5/asset/exchange/convert-execute")
+        .with_status(502)
+        .with_body("Bad Gateway")
+        .expect(1)
+        .create_async()
+        .await;
+
+    let asset = AssetHTTP::new(manager(&server));
+    let request = ConvertQuoteRequest::sell(ConvertAccount::Funding, "ETH", "BTC", dec("0.1"));
+    let result = asset.convert(&request).await;
+
+    assert!(matches!(
+        result,
+        Err(AppError::HttpStatus { status: 502, .. })
+    ));
+    execute.assert_async().await;
+}
+
+#[tokio::test]
+async fn reads_convert_coins_status_and_history() {
+    let mut server = Server::new_async().await;
+    let record = json!({
+        "accountType": "eb_convert_funding",
+        "exchangeTxId": "10100108106409343501030232064",
+        "userId": "100406395",
+        "fromCoin": "ETH",
+        "fromCoinType": "crypto",
+        "fromAmount": "0.1",
+        "toCoin": "BTC",
+        "toCoinType": "crypto",
+        "toAmount": "0.0000015",
+        "exchangeStatus": "success",
+        "extInfo": {"paramType": "", "paramValue": ""},
+        "convertRate": "0.000015",
+        "createdAt": "1697702140000",
+    });
+    let coins = server
+        .mock("GET", "/v5/asset/exchange/query-coin-list")
+        .match_query(Matcher::UrlEncoded(
+            "accountType".into(),
+            "eb_convert_uta".into(),
+        ))
+        .with_body(envelope(json!({
+            "coins": [{
+                "coin": "BTC", "fullName": "BTC", "icon": "", "iconNight": "",
+                "accuracyLength": 8, "coinType": "crypto", "balance": "0", "uBalance": "0",
+                "singleFromMinLimit": "0.0001", "singleFromMaxLimit": "20",
+                "disableFrom": false, "disableTo": false, "timePeriod": 0,
+            }],
+        })))
+        .create_async()
+        .await;
+    let status = reply(
+        &mut server,
+        "GET",
+        "/v5/asset/exchange/convert-result-query",
+        Matcher::Any,
+        json!({"result": record}),
+    )
+    .await;
+    let history = reply(
+        &mut server,
+        "GET",
+        "/v5/asset/exchange/query-convert-history",
+        Matcher::Any,
+        json!({"list": [record]}),
+    )
+    .await;
+
+    let asset = AssetHTTP::new(manager(&server));
+    let mut query = HashMap::new();
+    query.insert("accountType".to_owned(), "eb_convert_uta".to_owned());
+    let list = asset.get_convert_coin_list_typed(query).await.unwrap();
+    assert_eq!(list.coins[0].coin, "BTC");
+    assert_eq!(list.coins[0].single_from_max_limit, dec("20"));
+
+    let mut query = HashMap::new();
+    query.insert(
+        "quoteTxId".to_owned(),
+        "10100108106409343501030232064".to_owned(),
+    );
+    query.insert("accountType".to_owned(), "eb_convert_funding".to_owned());
+    let done = asset.get_convert_status_typed(query).await.unwrap();
+    assert_eq!(done.exchange_status, ConvertStatus::Success);
+    assert_eq!(done.account_type, ConvertAccount::Funding);
+    assert_eq!(done.created_at.timestamp_millis(), 1697702140000);
+
+    let page = asset
+        .get_convert_history_typed(HashMap::new())
+        .await
+        .unwrap();
+    assert_eq!(page.list, vec![done]);
+
+    coins.assert_async().await;
+    status.assert_async().await;
+    history.assert_async().await;
+}
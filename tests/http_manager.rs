@@ -0,0 +1,443 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bybit_rs::{
+    bybit::{
+        http_manager::{HttpManager, Manager},
+        rate_limit::{RateLimitMode, RateLimiter},
+        signer::{HmacSigner, Signer},
+    },
+    errors::app_error::AppError,
+};
+use mockito::{Matcher, Server};
+use reqwest::{
+    header::{HeaderName, HeaderValue},
+    Method,
+};
+use serde_json::json;
+
+fn envelope(ret_code: i64, result: serde_json::Value) -> String {
+    json!({
+        "retCode": ret_code,
+        "retMsg": if ret_code == 0 { "OK" } else { "rejected" },
+        "result": result,
+        "retExtInfo": {},
+        "time": 1688639403423u64,
+    })
+    .to_string()
+}
+
+fn manager(server: &Server) -> HttpManager {
+    HttpManager::builder("key".to_owned(), "secret".to_owned())
+        .base_url(&server.url())
+        .build()
+        .unwrap()
+        .with_retry_delay(Duration::from_millis(1))
+}
+
+#[tokio::test]
+async fn builder_settings_reach_the_server() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/v5/market/tickers")
+        .match_query(Matcher::UrlEncoded("category".into(), "spot".into()))
+        .match_header("user-agent", "my-bot/1.0")
+        .match_header("referer", "broker-id")
+        .with_body(envelope(0, json!({"list": []})))
+        .create_async()
+        .await;
+
+    let manager = HttpManager::builder("key".to_owned(), "secret".to_owned())
+        .base_url(&format!("{}/", server.url()))
+        .recv_window(10_000)
+        .timeout(Duration::from_secs(5))
+        .user_agent("my-bot/1.0")
+        .default_header(
+            HeaderName::from_static("referer"),
+            HeaderValue::from_static("broker-id"),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(manager.base_url(), server.url());
+    assert_eq!(manager.recv_window(), 10_000);
+
+    let mut query = HashMap::new();
+    query.insert("category".to_owned(), "spot".to_owned());
+    let result = manager
+        .submit_request(Method::GET, "/v5/market/tickers", query, false)
+        .await
+        .unwrap();
+    assert_eq!(result, json!({"list": []}));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn injected_client_rejects_client_settings() {
+    let built = HttpManager::builder("key".to_owned(), "secret".to_owned())
+        .client(reqwest::Client::new())
+        .timeout(Duration::from_secs(1))
+        .build();
+    assert!(matches!(built, Err(AppError::InvalidInput(_))));
+}
+
+#[tokio::test]
+async fn server_errors_are_retried() {
+    let mut server = Server::new_async().await;
+    let failing = server
+        .mock("GET", "/v5/position/list")
+        .with_status(502)
+        .with_body("<html>Bad Gateway</html>")
+        .expect(2)
+        .create_async()
+        .await;
+    // mockito moves on to the next mock once the failing one has had its two hits
+    let recovered = server
+        .mock("GET", "/v5/position/list")
+        .with_body(envelope(0, json!({"list": [1]})))
+        .create_async()
+        .await;
+
+    let result = manager(&server)
+        .submit_request(Method::GET, "/v5/position/list", HashMap::new(), true)
+        .await
+        .unwrap();
+    assert_eq!(result["list"], json!([1]));
+    failing.assert_async().await;
+    recovered.assert_async().await;
+}
+
+#[tokio::test]
+async fn order_creation_without_link_id_is_not_retried() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v5/order/create")
+        .with_status(502)
+        .with_body("Bad Gateway")
+        .expect(1)
+        .create_async()
+        .await;
+    let manager = manager(&server);
+
+    let order = json!({"category": "linear", "symbol": "BTCUSDT", "side": "Buy"});
+    let error = manager
+        .submit_post_request(Method::POST, "/v5/order/create", true, order)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, AppError::HttpStatus { status: 502, .. }));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn rate_limited_order_with_link_id_is_retried() {
+    let mut server = Server::new_async().await;
+    let limited = server
+        .mock("POST", "/v5/order/create")
+        .with_body(envelope(10006, json!({})))
+        .expect(1)
+        .create_async()
+        .await;
+    let placed = server
+        .mock("POST", "/v5/order/create")
+        .with_body(envelope(0, json!({"orderId": "1", "orderLinkId": "my-1"})))
+        .create_async()
+        .await;
+    let manager = manager(&server);
+
+    let order = json!({"category": "linear", "symbol": "BTCUSDT", "orderLinkId": "my-1"});
+    let result = manager
+        .submit_post_request(Method::POST, "/v5/order/create", true, order)
+        .await
+        .unwrap();
+    assert_eq!(result["orderId"], "1");
+    limited.assert_async().await;
+    placed.assert_async().await;
+}
+
+#[tokio::test]
+async fn writes_without_a_client_id_are_sent_once() {
+    let mut server = Server::new_async().await;
+    let mut mocks = Vec::new();
+    for path in [
+        "/v5/asset/withdraw/create",
+        "/v5/user/create-sub-api",
+        "/v5/asset/exchange/convert-execute",
+        "/v5/position/set-leverage",
+    ] {
+        let mock = server
+            .mock("POST", path)
+            .with_status(502)
+            .with_body("Bad Gateway")
+            .expect(1)
+            .create_async()
+            .await;
+        mocks.push((path, mock));
+    }
+    let manager = manager(&server);
+
+    for (path, mock) in mocks {
+        let error = manager
+            .submit_post_request(Method::POST, path, true, json!({"coin": "USDT"}))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, AppError::HttpStatus { status: 502, .. }),
+            "{}",
+            path
+        );
+        mock.assert_async().await;
+    }
+
+    // the query based POST path follows the same rule
+    let switch_mode = server
+        .mock("POST", "/v5/position/switch-mode")
+        .with_body(envelope(10016, json!({})))
+        .expect(1)
+        .create_async()
+        .await;
+    let error = manager
+        .submit_request(
+            Method::POST,
+            "/v5/position/switch-mode",
+            HashMap::new(),
+            true,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error.ret_code(), Some(10016));
+    switch_mode.assert_async().await;
+}
+
+#[tokio::test]
+async fn writes_with_a_client_id_are_retried() {
+    let mut server = Server::new_async().await;
+    let cases = [
+        (
+            "/v5/asset/withdraw/create",
+            json!({"coin": "USDT", "requestId": "w-1"}),
+        ),
+        (
+            "/v5/asset/transfer/inter-transfer",
+            json!({"coin": "USDT", "transferId": "t-1"}),
+        ),
+        (
+            "/v5/asset/transfer/universal-transfer",
+            json!({"coin": "USDT", "transferId": "t-2"}),
+        ),
+        (
+            "/v5/order/create-batch",
+            json!({"request": [{"orderLinkId": "a"}, {"orderLinkId": "b"}]}),
+        ),
+    ];
+    let manager = manager(&server);
+
+    for (path, body) in cases {
+        let failing = server
+            .mock("POST", path)
+            .with_status(503)
+            .with_body("Service Unavailable")
+            .expect(1)
+            .create_async()
+            .await;
+        let recovered = server
+            .mock("POST", path)
+            .with_body(envelope(0, json!({"id": "1"})))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let result = manager
+            .submit_post_request(Method::POST, path, true, &body)
+            .await
+            .unwrap();
+        assert_eq!(result["id"], "1", "{}", path);
+        failing.assert_async().await;
+        recovered.assert_async().await;
+    }
+
+    // a batch is only as safe as its least identified order
+    let partial = server
+        .mock("POST", "/v5/order/create-batch")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+    let body = json!({"request": [{"orderLinkId": "a"}, {"symbol": "BTCUSDT"}]});
+    manager
+        .submit_post_request(Method::POST, "/v5/order/create-batch", true, &body)
+        .await
+        .unwrap_err();
+    partial.assert_async().await;
+}
+
+#[tokio::test]
+async fn retries_stop_at_max_retries() {
+    let mut server = Server::new_async().await;
+    let failing = server
+        .mock("GET", "/v5/market/time")
+        .with_body(envelope(10016, json!({})))
+        .expect(3)
+        .create_async()
+        .await;
+
+    let error = manager(&server)
+        .with_max_retries(2)
+        .submit_request(Method::GET, "/v5/market/time", HashMap::new(), false)
+        .await
+        .unwrap_err();
+    assert_eq!(error.ret_code(), Some(10016));
+    failing.assert_async().await;
+}
+
+#[tokio::test]
+async fn rejections_and_ignored_codes() {
+    let mut server = Server::new_async().await;
+    server
+        .mock("POST", "/v5/position/set-leverage")
+        .with_body(envelope(110043, json!({})))
+        .create_async()
+        .await;
+
+    let body = json!({"category": "linear", "symbol": "BTCUSDT"});
+    let error = manager(&server)
+        .submit_post_request(Method::POST, "/v5/position/set-leverage", true, &body)
+        .await
+        .unwrap_err();
+    assert_eq!(error.ret_code(), Some(110043));
+
+    // "leverage not modified" is harmless for callers that set it on every start
+    let result = manager(&server)
+        .with_ignore_codes(vec![110043])
+        .submit_post_request(Method::POST, "/v5/position/set-leverage", true, &body)
+        .await
+        .unwrap();
+    assert_eq!(result, json!({}));
+}
+
+/// Replies `retCode` 0 only if `X-BAPI-SIGN` matches the raw query or body as received.
+fn check_signature(request: &mockito::Request) -> Vec<u8> {
+    let header = |name: &str| request.header(name)[0].to_str().unwrap().to_owned();
+    let signed = match request.path_and_query().split_once('?') {
+        Some((_, query)) => query.to_owned(),
+        None => String::from_utf8(request.body().unwrap().clone()).unwrap(),
+    };
+    let expected = HmacSigner::new("secret")
+        .sign(&format!(
+            "{}{}{}{}",
+            header("X-BAPI-TIMESTAMP"),
+            header("X-BAPI-API-KEY"),
+            header("X-BAPI-RECV-WINDOW"),
+            signed
+        ))
+        .unwrap();
+    let ret_code = if header("X-BAPI-SIGN") == expected {
+        0
+    } else {
+        10004
+    };
+    envelope(ret_code, json!({ "signed": signed })).into()
+}
+
+#[tokio::test]
+async fn signed_requests_send_the_string_that_was_signed() {
+    let mut server = Server::new_async().await;
+    let get = server
+        .mock("GET", "/v5/order/realtime")
+        .match_query(Matcher::Any)
+        .with_body_from_request(check_signature)
+        .expect(20)
+        .create_async()
+        .await;
+    let post = server
+        .mock("POST", "/v5/position/switch-mode")
+        .with_body_from_request(check_signature)
+        .create_async()
+        .await;
+    let manager = manager(&server);
+
+    // HashMap iteration order differs between maps, so repeat with fresh ones
+    for _ in 0..20 {
+        let mut query = HashMap::new();
+        query.insert("symbol".to_owned(), "BTCUSDT".to_owned());
+        query.insert("category".to_owned(), "linear".to_owned());
+        query.insert("settleCoin".to_owned(), "USDT".to_owned());
+        query.insert("orderLinkId".to_owned(), "a b&c".to_owned());
+        query.insert("limit".to_owned(), "50".to_owned());
+        let result = manager
+            .submit_request(Method::GET, "/v5/order/realtime", query, true)
+            .await
+            .unwrap();
+        assert_eq!(
+            result["signed"],
+            "category=linear&limit=50&orderLinkId=a+b%26c&settleCoin=USDT&symbol=BTCUSDT"
+        );
+    }
+
+    let mut body = HashMap::new();
+    body.insert("symbol".to_owned(), "BTCUSDT".to_owned());
+    body.insert("category".to_owned(), "linear".to_owned());
+    body.insert("mode".to_owned(), "3".to_owned());
+    let result = manager
+        .submit_request(Method::POST, "/v5/position/switch-mode", body, true)
+        .await
+        .unwrap();
+    assert_eq!(
+        result["signed"],
+        r#"{"category":"linear","mode":"3","symbol":"BTCUSDT"}"#
+    );
+    get.assert_async().await;
+    post.assert_async().await;
+}
+
+#[tokio::test]
+async fn limit_headers_drive_the_limiter_in_server_time() {
+    let mut server = Server::new_async().await;
+    let local = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    // the server clock is an hour behind, so the reset is an hour in the local past
+    let server_now = local - 3_600_000;
+    let realtime = server
+        .mock("GET", "/v5/order/realtime")
+        .match_query(Matcher::Any)
+        .with_header("X-Bapi-Limit", "50")
+        .with_header("X-Bapi-Limit-Status", "0")
+        .with_header(
+            "X-Bapi-Limit-Reset-Timestamp",
+            &(server_now + 500).to_string(),
+        )
+        .with_body(envelope(0, json!({"list": []})))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let manager = manager(&server).with_rate_limiter(RateLimiter::new(RateLimitMode::Reject));
+    manager.time_sync().record(local, local, server_now);
+
+    manager
+        .submit_request(Method::GET, "/v5/order/realtime", HashMap::new(), true)
+        .await
+        .unwrap();
+    match manager
+        .submit_request(Method::GET, "/v5/order/realtime", HashMap::new(), true)
+        .await
+    {
+        Err(AppError::Throttled { path, retry_after }) => {
+            assert_eq!(path, "/v5/order/realtime");
+            assert!(
+                retry_after > Duration::from_millis(300),
+                "{:?}",
+                retry_after
+            );
+            assert!(
+                retry_after <= Duration::from_millis(600),
+                "{:?}",
+                retry_after
+            );
+        }
+        other => panic!("expected a throttled call, got {:?}", other),
+    }
+    realtime.assert_async().await;
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use std::sync::Arc;
+
+use bybit_rs::{
+    bybit::{
+        http_manager::HttpManager,
+        instruments::InstrumentRegistry,
+        trade::{Trade, TradeHTTP},
+    },
+    errors::app_error::AppError,
+    models::{
+        enums::{Category, Side},
+        order::PlaceOrderRequest,
+    },
+};
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use serde_json::{json, Value};
+
+use common::{dec, envelope, manager};
+
+fn linear(symbol: &str) -> Value {
+    json!({
+        "symbol": symbol, "contractType": "LinearPerpetual", "status": "Trading",
+        "baseCoin": "BTC", "quoteCoin": "USDT", "settleCoin": "USDT", "priceScale": "1",
+        "leverageFilter": {"minLeverage": "1", "maxLeverage": "100.00", "leverageStep": "0.01"},
+        "priceFilter": {"minPrice": "0.50", "maxPrice": "999999.00", "tickSize": "0.50"},
+        "lotSizeFilter": {"maxOrderQty": "100.000", "minOrderQty": "0.001", "qtyStep": "0.001",
+                          "maxMktOrderQty": "50.000", "minNotionalValue": "5"}
+    })
+}
+
+async fn serve_instruments() -> (ServerGuard, Arc<HttpManager>, Vec<Mock>) {
+    let mut server = Server::new_async().await;
+    let second = server
+        .mock("GET", "/v5/market/instruments-info")
+        .match_query(Matcher::UrlEncoded("cursor".into(), "page2".into()))
+        .with_body(envelope(
+            json!({"category": "linear", "list": [linear("ETHUSDT")], "nextPageCursor": ""}),
+        ))
+        .expect(1)
+        .create_async()
+        .await;
+    let first = server
+        .mock("GET", "/v5/market/instruments-info")
+        .match_query(Matcher::Any)
+        .with_body(envelope(
+            json!({"category": "linear", "list": [linear("BTCUSDT")], "nextPageCursor": "page2"}),
+        ))
+        .expect(1)
+        .create_async()
+        .await;
+    let manager = manager(&server);
+    (server, manager, vec![first, second])
+}
+
+#[tokio::test]
+async fn registry_pages_through_and_caches_instruments() {
+    let (_server, manager, pages) = serve_instruments().await;
+    let registry = InstrumentRegistry::new(manager);
+
+    assert!(registry.cached(Category::Linear, "BTCUSDT").is_none());
+    assert_eq!(registry.refresh(Category::Linear).await.unwrap(), 2);
+    let eth = registry.get(Category::Linear, "ETHUSDT").await.unwrap();
+    assert_eq!(eth.price_filter.tick_size, dec("0.50"));
+    assert_eq!(
+        registry
+            .round_price(Category::Linear, "BTCUSDT", dec("25000.26"))
+            .await
+            .unwrap(),
+        dec("25000.50")
+    );
+    assert_eq!(
+        registry
+            .round_qty(Category::Linear, "BTCUSDT", dec("0.0129"))
+            .await
+            .unwrap(),
+        dec("0.012")
+    );
+    assert!(matches!(
+        registry.get(Category::Linear, "DOGEUSDT").await,
+        Err(AppError::InvalidInput(_))
+    ));
+    // Both pages were fetched once; everything after came from the cache.
+    for page in pages {
+        page.assert_async().await;
+    }
+}
+
+#[tokio::test]
+async fn orders_are_checked_against_the_filters() {
+    let (_server, manager, _pages) = serve_instruments().await;
+    let registry = InstrumentRegistry::new(manager);
+    let limit = |qty: &str, price: &str| {
+        PlaceOrderRequest::limit(Category::Linear, "BTCUSDT", Side::Buy, dec(qty), dec(price))
+    };
+    let rejected = |result: Result<(), AppError>| matches!(result, Err(AppError::InvalidInput(_)));
+
+    registry
+        .validate_order(&limit("0.01", "25000.5"))
+        .await
+        .unwrap();
+    assert!(rejected(
+        registry.validate_order(&limit("0.0105", "25000.5")).await
+    ));
+    assert!(rejected(
+        registry.validate_order(&limit("0.01", "25000.2")).await
+    ));
+    assert!(rejected(
+        registry.validate_order(&limit("0.0001", "25000")).await
+    ));
+    assert!(rejected(
+        registry.validate_order(&limit("101", "25000")).await
+    ));
+    assert!(rejected(
+        registry.validate_order(&limit("0.001", "1000")).await
+    ));
+    let market = PlaceOrderRequest::market(Category::Linear, "BTCUSDT", Side::Sell, dec("60"));
+    assert!(rejected(registry.validate_order(&market).await));
+}
+
+#[tokio::test]
+async fn trade_client_rejects_orders_before_sending() {
+    let (mut server, manager, _pages) = serve_instruments().await;
+    let create = server
+        .mock("POST", "/v5/order/create")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let registry = Arc::new(InstrumentRegistry::new(manager.clone()));
+    let trade = TradeHTTP::new(manager).with_instruments(registry);
+    let request = PlaceOrderRequest::limit(
+        Category::Linear,
+        "BTCUSDT",
+        Side::Buy,
+        dec("0.01"),
+        dec("25000.3"),
+    );
+    assert!(matches!(
+        trade.place_order_typed(&request).await,
+        Err(AppError::InvalidInput(_))
+    ));
+    create.assert_async().await;
+}
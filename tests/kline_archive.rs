@@ -0,0 +1,245 @@
+#![cfg(feature = "archive")]
+
+mod common;
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use bybit_rs::{
+    bybit::kline_archive::{KlineArchive, KlineKind, KlineRequest},
+    models::enums::{Category, Interval},
+};
+use chrono::{Duration, TimeZone, Utc};
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use serde_json::json;
+
+use common::manager;
+
+const MINUTE: i64 = 60_000;
+const FIRST_BAR: i64 = 1_700_000_040_000;
+/// Bybit has no bar for this minute.
+const HOLE: i64 = FIRST_BAR + 1500 * MINUTE;
+
+///
+/// Serves one minute bars from `FIRST_BAR` on, newest first, like `/v5/market/kline`.
+///
+async fn klines(
+    server: &mut ServerGuard,
+    path: &str,
+    volume: bool,
+    requests: Arc<AtomicUsize>,
+) -> Mock {
+    server
+        .mock("GET", path)
+        .match_query(Matcher::Any)
+        .with_body_from_request(move |request| {
+            requests.fetch_add(1, Ordering::SeqCst);
+            let query: HashMap<String, String> = url::form_urlencoded::parse(
+                request
+                    .path_and_query()
+                    .split('?')
+                    .nth(1)
+                    .unwrap()
+                    .as_bytes(),
+            )
+            .into_owned()
+            .collect();
+            let start: i64 = query["start"].parse().unwrap();
+            let end: i64 = query["end"].parse().unwrap();
+            let limit: usize = query["limit"].parse().unwrap();
+            assert_eq!(query["interval"], "1");
+
+            let last = FIRST_BAR + (end - FIRST_BAR).div_euclid(MINUTE) * MINUTE;
+            let list: Vec<Vec<String>> = (0..)
+                .map(|i| last - i * MINUTE)
+                .take_while(|bar| *bar >= start.max(FIRST_BAR))
+                .filter(|bar| *bar != HOLE)
+                .take(limit)
+                .map(|bar| {
+                    let price = (bar - FIRST_BAR) / MINUTE;
+                    let mut row = vec![
+                        bar.to_string(),
+                        price.to_string(),
+                        (price + 2).to_string(),
+                        (price - 1).to_string(),
+                        format!("{}.5", price),
+                    ];
+                    if volume {
+                        row.extend(["10".to_owned(), "100".to_owned()]);
+                    }
+                    row
+                })
+                .collect();
+            json!({
+                "retCode": 0, "retMsg": "OK", "retExtInfo": {}, "time": 0,
+                "result": {"category": "linear", "symbol": "BTCUSDT", "list": list},
+            })
+            .to_string()
+            .into()
+        })
+        .create_async()
+        .await
+}
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("bybit_rs_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[tokio::test]
+async fn backfills_in_pages_and_reports_gaps() {
+    let mut server = Server::new_async().await;
+    let requests = Arc::new(AtomicUsize::new(0));
+    let _mock = klines(&mut server, "/v5/market/kline", true, requests.clone()).await;
+    let dir = temp_dir("backfill");
+
+    let archive = KlineArchive::new(manager(&server), &dir);
+    let start = Utc.timestamp_millis_opt(FIRST_BAR).unwrap();
+    let request = KlineRequest::new(
+        Category::Linear,
+        "BTCUSDT",
+        Interval::Minute1,
+        start,
+        start + Duration::minutes(2499),
+    );
+    let report = archive.sync(&request).await.unwrap();
+
+    assert_eq!(report.added, 2499);
+    assert_eq!(report.total, 2499);
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+    assert_eq!(report.gaps.len(), 1);
+    assert_eq!(report.gaps[0].after.timestamp_millis(), HOLE - MINUTE);
+    assert_eq!(report.gaps[0].before.timestamp_millis(), HOLE + MINUTE);
+    assert_eq!(report.gaps[0].missing, 1);
+    assert!(report.csv_path.ends_with("linear/BTCUSDT/kline_1.csv"));
+
+    let bars = archive.read(&request).unwrap();
+    assert_eq!(bars.len(), 2499);
+    assert_eq!(bars[0].start_time, start);
+    assert_eq!(bars[0].close.to_string(), "0.5");
+    assert_eq!(bars[0].volume.unwrap().to_string(), "10");
+
+    let parquet = SerializedFileReader::new(File::open(&report.parquet_path).unwrap()).unwrap();
+    assert_eq!(parquet.metadata().file_metadata().num_rows(), 2499);
+    assert_eq!(
+        parquet
+            .metadata()
+            .file_metadata()
+            .schema_descr()
+            .num_columns(),
+        7
+    );
+
+    let again = archive.sync(&request).await.unwrap();
+    assert_eq!(again.added, 0);
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn known_gaps_are_not_requested_again() {
+    let mut server = Server::new_async().await;
+    let requests = Arc::new(AtomicUsize::new(0));
+    let _mock = klines(&mut server, "/v5/market/kline", true, requests.clone()).await;
+    let dir = temp_dir("known_gaps");
+
+    let archive = KlineArchive::new(manager(&server), &dir);
+    let first = Utc.timestamp_millis_opt(FIRST_BAR).unwrap();
+    // starts before the first bar Bybit has, like a range reaching back past a listing
+    let request = KlineRequest::new(
+        Category::Linear,
+        "BTCUSDT",
+        Interval::Minute1,
+        first - Duration::minutes(30),
+        first + Duration::minutes(2499),
+    );
+    let report = archive.sync(&request).await.unwrap();
+    assert_eq!(report.added, 2499);
+    assert_eq!(report.gaps.len(), 1);
+    let after_backfill = requests.load(Ordering::SeqCst);
+
+    let again = archive.sync(&request).await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), after_backfill);
+    assert_eq!(again.added, 0);
+    assert_eq!(again.gaps, report.gaps);
+
+    let gaps = std::fs::read_to_string(archive.gaps_path(&request)).unwrap();
+    assert_eq!(
+        gaps.lines().collect::<Vec<_>>(),
+        vec![
+            "from,to".to_owned(),
+            format!("{},{}", FIRST_BAR - 30 * MINUTE, FIRST_BAR - 1),
+            format!("{},{}", HOLE - MINUTE + 1, HOLE + MINUTE - 1),
+        ]
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn resumes_from_the_last_stored_bar() {
+    let mut server = Server::new_async().await;
+    let requests = Arc::new(AtomicUsize::new(0));
+    let _mock = klines(&mut server, "/v5/market/kline", true, requests.clone()).await;
+    let dir = temp_dir("resume");
+
+    let archive = KlineArchive::new(manager(&server), &dir);
+    let start = Utc.timestamp_millis_opt(FIRST_BAR).unwrap();
+    let request = KlineRequest::new(
+        Category::Linear,
+        "BTCUSDT",
+        Interval::Minute1,
+        start,
+        start + Duration::minutes(99),
+    );
+    assert_eq!(archive.sync(&request).await.unwrap().added, 100);
+    let before = requests.load(Ordering::SeqCst);
+
+    let unchanged = archive.sync(&request).await.unwrap();
+    assert_eq!(unchanged.added, 0);
+    assert_eq!(requests.load(Ordering::SeqCst), before);
+
+    let mut longer = request.clone();
+    longer.end = start + Duration::minutes(109);
+    let report = archive.sync(&longer).await.unwrap();
+    assert_eq!(report.added, 10);
+    assert_eq!(report.total, 110);
+    assert!(report.gaps.is_empty());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn stores_price_klines_without_volume() {
+    let mut server = Server::new_async().await;
+    let requests = Arc::new(AtomicUsize::new(0));
+    let _mock = klines(&mut server, "/v5/market/mark-price-kline", false, requests).await;
+    let dir = temp_dir("mark");
+
+    let archive = KlineArchive::new(manager(&server), &dir);
+    let start = Utc.timestamp_millis_opt(FIRST_BAR).unwrap();
+    let request = KlineRequest::new(
+        Category::Linear,
+        "BTCUSDT",
+        Interval::Minute1,
+        start,
+        start + Duration::minutes(9),
+    )
+    .kind(KlineKind::Mark);
+    let report = archive.sync(&request).await.unwrap();
+
+    assert_eq!(report.total, 10);
+    assert!(report
+        .csv_path
+        .ends_with("linear/BTCUSDT/mark_price_kline_1.csv"));
+    let csv = std::fs::read_to_string(&report.csv_path).unwrap();
+    assert_eq!(csv.lines().next(), Some("start_time,open,high,low,close"));
+    assert!(archive.read(&request).unwrap()[0].volume.is_none());
+    std::fs::remove_dir_all(dir).unwrap();
+}
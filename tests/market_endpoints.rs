@@ -0,0 +1,129 @@
+mod common;
+
+use std::collections::HashMap;
+
+use bybit_rs::bybit::market::{Market, MarketHTTP};
+use futures::TryStreamExt;
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use serde_json::{json, Value};
+
+use common::{envelope, manager};
+
+async fn ok(server: &mut ServerGuard, path: &str, query: Matcher, result: Value) -> Mock {
+    server
+        .mock("GET", path)
+        .match_query(query)
+        .with_body(envelope(result))
+        .create_async()
+        .await
+}
+
+#[tokio::test]
+async fn insurance_uses_its_own_route() {
+    let mut server = Server::new_async().await;
+    let mock = ok(
+        &mut server,
+        "/v5/market/insurance",
+        Matcher::Any,
+        json!({"updatedTime": "1714003200000", "list": [{"coin": "USDT", "balance": "1", "value": "1"}]}),
+    )
+    .await;
+
+    let market = MarketHTTP::new(manager(&server));
+    let insurance = market.get_insurance_typed(HashMap::new()).await.unwrap();
+    assert_eq!(insurance.list[0].coin, "USDT");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn new_endpoints_hit_their_routes() {
+    let mut server = Server::new_async().await;
+    let mut mocks = Vec::new();
+    for path in [
+        "/v5/market/account-ratio",
+        "/v5/market/rpi_orderbook",
+        "/v5/market/price-limit",
+        "/v5/market/index-price-components",
+        "/v5/market/new-delivery-price",
+        "/v5/spot-margin-trade/data",
+        "/v5/spot-margin-trade/collateral",
+    ] {
+        mocks.push(ok(&mut server, path, Matcher::Any, json!({"path": path})).await);
+    }
+
+    let market = MarketHTTP::new(manager(&server));
+    let results = [
+        market.get_long_short_ratio(HashMap::new()).await,
+        market.get_rpi_orderbook(HashMap::new()).await,
+        market.get_order_price_limit(HashMap::new()).await,
+        market.get_index_price_components(HashMap::new()).await,
+        market.get_new_delivery_price(HashMap::new()).await,
+        market.get_spot_margin_data(HashMap::new()).await,
+        market.get_spot_margin_collateral(HashMap::new()).await,
+    ];
+    for result in results {
+        assert!(result.unwrap()["path"].is_string());
+    }
+    for mock in mocks {
+        mock.assert_async().await;
+    }
+}
+
+#[tokio::test]
+async fn instruments_stream_follows_the_cursor_within_a_category() {
+    let mut server = Server::new_async().await;
+    let path = "/v5/market/instruments-info";
+    let second = ok(
+        &mut server,
+        path,
+        Matcher::AllOf(vec![
+            Matcher::UrlEncoded("category".into(), "linear".into()),
+            Matcher::UrlEncoded("cursor".into(), "next".into()),
+        ]),
+        json!({"category": "linear", "list": [{"symbol": "ETHUSDT"}], "nextPageCursor": ""}),
+    )
+    .await;
+    let first = ok(
+        &mut server,
+        path,
+        Matcher::UrlEncoded("category".into(), "linear".into()),
+        json!({"category": "linear", "list": [{"symbol": "BTCUSDT"}], "nextPageCursor": "next"}),
+    )
+    .await;
+
+    let market = MarketHTTP::new(manager(&server));
+    let mut query = HashMap::new();
+    query.insert("category".to_owned(), "linear".to_owned());
+    let instruments: Vec<Value> = market
+        .get_instruments_info_stream(query)
+        .try_collect()
+        .await
+        .unwrap();
+    let symbols: Vec<&str> = instruments
+        .iter()
+        .map(|instrument| instrument["symbol"].as_str().unwrap())
+        .collect();
+    assert_eq!(symbols, ["BTCUSDT", "ETHUSDT"]);
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+#[tokio::test]
+async fn long_short_ratio_is_typed() {
+    let mut server = Server::new_async().await;
+    let _mock = ok(
+        &mut server,
+        "/v5/market/account-ratio",
+        Matcher::Any,
+        json!({"list": [{"symbol": "BTCUSDT", "buyRatio": "0.5495", "sellRatio": "0.4505", "timestamp": "1695772800000"}], "nextPageCursor": ""}),
+    )
+    .await;
+
+    let market = MarketHTTP::new(manager(&server));
+    let page = market
+        .get_long_short_ratio_typed(HashMap::new())
+        .await
+        .unwrap();
+    assert_eq!(page.list[0].buy_ratio.to_string(), "0.5495");
+    assert_eq!(page.list[0].timestamp.timestamp_millis(), 1_695_772_800_000);
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use std::collections::HashMap;
+
+use bybit_rs::{
+    bybit::market::{Market, MarketHTTP},
+    models::{
+        enums::{Category, Side},
+        market::{
+            HistoricalVolatility, InstrumentInfo, Kline, Klines, Orderbook, PublicTrade,
+            RiskLimit, Tickers,
+        },
+        Page,
+    },
+};
+use chrono::{TimeZone, Utc};
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+use common::{dec, manager};
+
+#[tokio::test]
+async fn typed_kline_parses_candle_arrays() {
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/v5/market/kline")
+        .match_query(Matcher::Any)
+        .with_body(
+            json!({
+                "retCode": 0,
+                "retMsg": "OK",
+                "result": {
+                    "category": "inverse",
+                    "symbol": "BTCUSD",
+                    "list": [
+                        ["1670608800000", "17071", "17073", "17027", "17055.5", "268611", "15.74462667"],
+                        ["1670605200000", "17071.5", "17071.5", "17061", "17071", "4177", "0.24469757"],
+                    ],
+                },
+                "retExtInfo": {},
+                "time": 1672025956592u64,
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let market = MarketHTTP::new(manager(&server));
+    let klines: Klines<Kline> = market.get_kline_typed(HashMap::new()).await.unwrap();
+
+    assert_eq!(klines.category, Category::Inverse);
+    assert_eq!(klines.list.len(), 2);
+    assert_eq!(
+        klines.list[0],
+        Kline {
+            start_time: Utc.timestamp_millis_opt(1670608800000).unwrap(),
+            open: dec("17071"),
+            high: dec("17073"),
+            low: dec("17027"),
+            close: dec("17055.5"),
+            volume: dec("268611"),
+            turnover: dec("15.74462667"),
+        }
+    );
+}
+
+#[test]
+fn tickers_pick_the_shape_from_the_category() {
+    let spot: Tickers = serde_json::from_value(json!({
+        "category": "spot",
+        "list": [{
+            "symbol": "BTCUSDT", "bid1Price": "20517.96", "bid1Size": "2", "ask1Price": "20527.77",
+            "ask1Size": "1.862172", "lastPrice": "20533.13", "prevPrice24h": "20393.48",
+            "price24hPcnt": "0.0068", "highPrice24h": "21128.12", "lowPrice24h": "20318.89",
+            "turnover24h": "243765620.65899866", "volume24h": "11801.27771", "usdIndexPrice": "20784.12009279"
+        }]
+    }))
+    .unwrap();
+    match spot {
+        Tickers::Spot(list) => assert_eq!(list[0].bid1_price, Some(dec("20517.96"))),
+        other => panic!("unexpected tickers {:?}", other),
+    }
+
+    let inverse: Tickers = serde_json::from_value(json!({
+        "category": "inverse",
+        "list": [{
+            "symbol": "BTCUSD", "lastPrice": "16597.00", "indexPrice": "16598.54", "markPrice": "16596.00",
+            "prevPrice24h": "16464.50", "price24hPcnt": "0.008047", "highPrice24h": "30912.50",
+            "lowPrice24h": "15700.00", "prevPrice1h": "16595.50", "openInterest": "373504107",
+            "openInterestValue": "22505.67", "turnover24h": "2352.94950046", "volume24h": "49337318",
+            "fundingRate": "-0.001034", "nextFundingTime": "1672387200000", "predictedDeliveryPrice": "",
+            "basisRate": "", "deliveryFeeRate": "", "deliveryTime": "0", "ask1Size": "1", "bid1Price": "16596.00",
+            "ask1Price": "16597.50", "bid1Size": "1", "basis": ""
+        }]
+    }))
+    .unwrap();
+    match inverse {
+        Tickers::Linear(list) => {
+            assert_eq!(list[0].funding_rate, Some(dec("-0.001034")));
+            assert_eq!(list[0].delivery_time, None);
+            assert_eq!(list[0].basis_rate, None);
+            assert_eq!(
+                list[0].next_funding_time,
+                Utc.timestamp_millis_opt(1672387200000).single()
+            );
+        }
+        other => panic!("unexpected tickers {:?}", other),
+    }
+}
+
+#[test]
+fn list_results_parse() {
+    let orderbook: Orderbook = serde_json::from_value(json!({
+        "s": "BTCUSDT",
+        "a": [["16638.64", "0.008479"]],
+        "b": [["16638.27", "0.305749"], ["16638.1", "0.1"]],
+        "ts": 1672765737733u64,
+        "u": 5277055,
+        "seq": 2341,
+    }))
+    .unwrap();
+    assert_eq!(orderbook.bids[1].price, dec("16638.1"));
+    assert_eq!(orderbook.asks[0].size, dec("0.008479"));
+    assert_eq!(orderbook.created_time, None);
+
+    let instruments: Page<InstrumentInfo> = serde_json::from_value(json!({
+        "category": "linear",
+        "list": [{
+            "symbol": "BTCUSDT", "contractType": "LinearPerpetual", "status": "Trading",
+            "baseCoin": "BTC", "quoteCoin": "USDT", "launchTime": "1585526400000", "deliveryTime": "0",
+            "deliveryFeeRate": "", "priceScale": "2",
+            "leverageFilter": {"minLeverage": "1", "maxLeverage": "100.00", "leverageStep": "0.01"},
+            "priceFilter": {"minPrice": "0.10", "maxPrice": "199999.80", "tickSize": "0.10"},
+            "lotSizeFilter": {"maxOrderQty": "100.000", "minOrderQty": "0.001", "qtyStep": "0.001",
+                              "postOnlyMaxOrderQty": "1000.000"},
+            "unifiedMarginTrade": true, "fundingInterval": 480, "settleCoin": "USDT"
+        }],
+        "nextPageCursor": ""
+    }))
+    .unwrap();
+    let btc = &instruments.list[0];
+    assert_eq!(btc.price_scale, Some(2));
+    assert_eq!(btc.price_filter.tick_size, dec("0.10"));
+    assert_eq!(btc.lot_size_filter.qty_step, Some(dec("0.001")));
+    assert_eq!(btc.delivery_time, None);
+    assert_eq!(btc.funding_interval, Some(480));
+
+    let trades: Page<PublicTrade> = serde_json::from_value(json!({
+        "category": "spot",
+        "list": [{"execId": "2100000000007764263", "symbol": "BTCUSDT", "price": "16618.49",
+                  "size": "0.00012", "side": "Buy", "time": "1672052955758", "isBlockTrade": false}]
+    }))
+    .unwrap();
+    assert_eq!(trades.list[0].side, Side::Buy);
+
+    let volatility: Vec<HistoricalVolatility> = serde_json::from_value(json!([
+        {"period": 7, "value": "0.27545620", "time": "1672232400000"}
+    ]))
+    .unwrap();
+    assert_eq!(volatility[0].value, dec("0.2754562"));
+
+    let risk: Page<RiskLimit> = serde_json::from_value(json!({
+        "category": "inverse",
+        "list": [{"id": 1, "symbol": "BTCUSD", "riskLimitValue": "150", "maintenanceMargin": "0.5",
+                  "initialMargin": "1", "isLowestRisk": 1, "maxLeverage": "100.00", "mmDeduction": ""}]
+    }))
+    .unwrap();
+    assert_eq!(risk.list[0].mm_deduction, None);
+    assert_eq!(risk.list[0].is_lowest_risk, 1);
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use bybit_rs::{
+    bybit::trade::{Trade, TradeHTTP},
+    errors::app_error::AppError,
+    models::{
+        enums::{Category, OrderType, PositionIdx, Side, TimeInForce, TpslMode, TriggerBy},
+        order::PlaceOrderRequest,
+    },
+};
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+use common::{dec, manager};
+
+#[test]
+fn serializes_to_the_place_order_body() {
+    let request = PlaceOrderRequest::limit(
+        Category::Linear,
+        "BTCUSDT",
+        Side::Buy,
+        dec("0.01"),
+        dec("25000.5"),
+    )
+    .time_in_force(TimeInForce::GoodTillCancel)
+    .position_idx(PositionIdx::HedgeBuy)
+    .take_profit(dec("30000"))
+    .tp_trigger_by(TriggerBy::MarkPrice)
+    .reduce_only(false)
+    .order_link_id("my-order-1");
+    request.validate().unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        json!({
+            "category": "linear",
+            "symbol": "BTCUSDT",
+            "side": "Buy",
+            "orderType": "Limit",
+            "qty": "0.01",
+            "price": "25000.5",
+            "timeInForce": "GTC",
+            "orderLinkId": "my-order-1",
+            "positionIdx": 1,
+            "takeProfit": "30000",
+            "tpTriggerBy": "MarkPrice",
+            "reduceOnly": false,
+        })
+    );
+
+    let query = request.to_query().unwrap();
+    assert_eq!(query["qty"], "0.01");
+    assert_eq!(query["positionIdx"], "1");
+    assert_eq!(query["reduceOnly"], "false");
+    assert!(!query.contains_key("stopLoss"));
+}
+
+#[tokio::test]
+async fn typed_orders_are_posted_with_json_numbers_and_booleans() {
+    let mut server = Server::new_async().await;
+    let create = server
+        .mock("POST", "/v5/order/create")
+        .match_body(Matcher::Json(json!({
+            "category": "linear",
+            "symbol": "BTCUSDT",
+            "side": "Sell",
+            "orderType": "Market",
+            "qty": "0.5",
+            "positionIdx": 2,
+            "reduceOnly": true,
+            "closeOnTrigger": false,
+        })))
+        .with_body(
+            json!({
+                "retCode": 0,
+                "retMsg": "OK",
+                "result": {"orderId": "1321003749386327552", "orderLinkId": ""},
+                "retExtInfo": {},
+                "time": 0,
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let manager = manager(&server);
+
+    let request = PlaceOrderRequest::market(Category::Linear, "BTCUSDT", Side::Sell, dec("0.5"))
+        .position_idx(PositionIdx::HedgeSell)
+        .reduce_only(true)
+        .close_on_trigger(false);
+    let ack = TradeHTTP::new(manager)
+        .place_order_typed(&request)
+        .await
+        .unwrap();
+
+    assert_eq!(ack.order_id, "1321003749386327552");
+    create.assert_async().await;
+}
+
+#[test]
+fn rejects_invalid_combinations() {
+    let rejected =
+        |request: PlaceOrderRequest| matches!(request.validate(), Err(AppError::InvalidInput(_)));
+    let market = || PlaceOrderRequest::market(Category::Linear, "BTCUSDT", Side::Sell, dec("1"));
+
+    assert!(market().validate().is_ok());
+    assert!(rejected(PlaceOrderRequest::new(
+        Category::Linear,
+        "BTCUSDT",
+        Side::Buy,
+        OrderType::Limit,
+        dec("1")
+    )));
+    assert!(rejected(market().time_in_force(TimeInForce::PostOnly)));
+    assert!(rejected(PlaceOrderRequest::market(
+        Category::Linear,
+        "",
+        Side::Sell,
+        dec("1")
+    )));
+    assert!(rejected(PlaceOrderRequest::market(
+        Category::Linear,
+        "BTCUSDT",
+        Side::Sell,
+        dec("0")
+    )));
+    assert!(rejected(market().price(dec("-1"))));
+    assert!(rejected(market().tp_trigger_by(TriggerBy::LastPrice)));
+    assert!(rejected(
+        market().take_profit(dec("30000")).tp_limit(dec("29900"))
+    ));
+    assert!(market()
+        .take_profit(dec("30000"))
+        .tp_limit(dec("29900"))
+        .tpsl_mode(TpslMode::Partial)
+        .validate()
+        .is_ok());
+    assert!(rejected(
+        PlaceOrderRequest::market(Category::Spot, "BTCUSDT", Side::Buy, dec("1")).reduce_only(true)
+    ));
+    assert!(rejected(PlaceOrderRequest::market(
+        Category::Option,
+        "BTC-29DEC23-40000-C",
+        Side::Buy,
+        dec("1")
+    )));
+}
+
+#[test]
+fn enums_round_trip_through_strings() {
+    assert_eq!(
+        "IOC".parse::<TimeInForce>().unwrap(),
+        TimeInForce::ImmediateOrCancel
+    );
+    assert_eq!(Category::Inverse.to_string(), "inverse");
+    assert!("Stop".parse::<OrderType>().is_err());
+    assert_eq!(
+        serde_json::from_value::<PositionIdx>(json!(2)).unwrap(),
+        PositionIdx::HedgeSell
+    );
+    assert!(serde_json::from_value::<Side>(json!("buy")).is_err());
+}
@@ -0,0 +1,223 @@
+mod common;
+
+use bybit_rs::bybit::{
+    market::{Market, MarketHTTP},
+    orderbook::{BookSide, BookUpdate, OrderBook, SyncedOrderBook},
+    websocket_stream::{PublicStream, StreamEvent, StreamHandle, TopicMessage},
+};
+use futures::channel::mpsc;
+use mockito::{Matcher, Server};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+use common::{accept, dec, manager, next_event, read_op};
+
+fn message(kind: &str, u: u64, bids: Value, asks: Value) -> TopicMessage {
+    serde_json::from_value(json!({
+        "topic": "orderbook.50.BTCUSDT",
+        "type": kind,
+        "ts": 1672304484978u64,
+        "data": {"s": "BTCUSDT", "b": bids, "a": asks, "u": u, "seq": u * 10},
+    }))
+    .unwrap()
+}
+
+fn snapshot() -> TopicMessage {
+    message(
+        "snapshot",
+        100,
+        json!([["16493.50", "0.006"], ["16493.00", "0.100"]]),
+        json!([["16611.00", "0.029"], ["16612.00", "0.213"]]),
+    )
+}
+
+#[test]
+fn snapshot_and_deltas_are_merged() {
+    let mut book = OrderBook::new("BTCUSDT");
+    assert_eq!(book.apply(&snapshot()).unwrap(), BookUpdate::Snapshot);
+
+    let delta = message(
+        "delta",
+        101,
+        json!([["16493.50", "0"], ["16494.00", "1.5"]]),
+        json!([["16611.00", "0.5"]]),
+    );
+    assert_eq!(book.apply(&delta).unwrap(), BookUpdate::Delta);
+
+    assert_eq!(book.best_bid(), Some((dec("16494.00"), dec("1.5"))));
+    assert_eq!(book.best_ask(), Some((dec("16611.00"), dec("0.5"))));
+    assert_eq!(book.depth_at(BookSide::Bid, dec("16493.50")), Decimal::ZERO);
+    assert_eq!(book.spread(), Some(dec("117")));
+    assert_eq!(book.mid(), Some(dec("16552.5")));
+    assert_eq!(book.update_id(), 101);
+}
+
+#[test]
+fn gap_invalidates_until_next_snapshot() {
+    let mut book = OrderBook::new("BTCUSDT");
+    book.apply(&snapshot()).unwrap();
+
+    let outdated = message("delta", 100, json!([]), json!([]));
+    assert_eq!(book.apply(&outdated).unwrap(), BookUpdate::Outdated);
+
+    let skipped = message("delta", 103, json!([]), json!([]));
+    assert_eq!(
+        book.apply(&skipped).unwrap(),
+        BookUpdate::Gap {
+            expected: 101,
+            received: 103
+        }
+    );
+    assert!(!book.is_synced());
+    assert_eq!(book.best_bid(), None);
+
+    let next = message("delta", 104, json!([]), json!([]));
+    assert_eq!(book.apply(&next).unwrap(), BookUpdate::AwaitingSnapshot);
+
+    book.apply(&snapshot()).unwrap();
+    assert!(book.is_synced());
+}
+
+// A public stream connected to a local server that forwards every op it receives.
+async fn stream_handle() -> (StreamHandle, mpsc::UnboundedReceiver<Value>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let (ops_tx, ops) = mpsc::unbounded();
+    tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        loop {
+            let op = read_op(&mut socket).await;
+            if ops_tx.unbounded_send(op).is_err() {
+                break;
+            }
+        }
+    });
+    let (handle, _events) = PublicStream::from_url(url).connect().await.unwrap();
+    (handle, ops)
+}
+
+fn event(message: TopicMessage) -> StreamEvent {
+    StreamEvent::Message(message)
+}
+
+#[tokio::test]
+async fn gap_resubscribes_without_rest() {
+    let (handle, mut ops) = stream_handle().await;
+    let mut synced = SyncedOrderBook::new(50, "BTCUSDT", handle);
+    synced.subscribe().unwrap();
+    assert_eq!(next_event(&mut ops).await["op"], "subscribe");
+
+    synced.handle_event(&event(snapshot())).await.unwrap();
+    let gap = message("delta", 105, json!([]), json!([]));
+    assert_eq!(
+        synced.handle_event(&event(gap)).await.unwrap(),
+        Some(BookUpdate::Gap {
+            expected: 101,
+            received: 105
+        })
+    );
+
+    let unsubscribe = next_event(&mut ops).await;
+    let subscribe = next_event(&mut ops).await;
+    assert_eq!(
+        unsubscribe,
+        json!({"op": "unsubscribe", "args": ["orderbook.50.BTCUSDT"]})
+    );
+    assert_eq!(
+        subscribe,
+        json!({"op": "subscribe", "args": ["orderbook.50.BTCUSDT"]})
+    );
+    assert!(!synced.book().is_synced());
+}
+
+#[tokio::test]
+async fn gap_resyncs_from_rest_and_drops_older_deltas() {
+    let mut server = Server::new_async().await;
+    let orderbook = server
+        .mock("GET", "/v5/market/orderbook")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("category".into(), "linear".into()),
+            Matcher::UrlEncoded("symbol".into(), "BTCUSDT".into()),
+            Matcher::UrlEncoded("limit".into(), "50".into()),
+        ]))
+        .with_body(
+            json!({"retCode": 0, "retMsg": "OK", "retExtInfo": {}, "time": 0, "result": {
+                "s": "BTCUSDT",
+                "b": [["16500.00", "2"]],
+                "a": [["16600.00", "3"]],
+                "ts": 1672304490000u64,
+                "u": 900000,
+                "seq": 1200,
+            }})
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let manager = manager(&server);
+
+    let (handle, _ops) = stream_handle().await;
+    let mut synced = SyncedOrderBook::new(50, "BTCUSDT", handle)
+        .with_rest_resync(MarketHTTP::new(manager), "linear");
+    synced.handle_event(&event(snapshot())).await.unwrap();
+
+    let gap = message("delta", 110, json!([]), json!([]));
+    assert!(matches!(
+        synced.handle_event(&event(gap)).await.unwrap(),
+        Some(BookUpdate::Gap { .. })
+    ));
+    orderbook.assert_async().await;
+    assert_eq!(synced.book().best_bid(), Some((dec("16500.00"), dec("2"))));
+
+    // u 120 is newer than the stream book but its seq 1200 is already in the REST snapshot
+    let stale = message("delta", 120, json!([["16500.00", "0"]]), json!([]));
+    assert_eq!(
+        synced.handle_event(&event(stale)).await.unwrap(),
+        Some(BookUpdate::Outdated)
+    );
+    assert_eq!(synced.book().best_bid(), Some((dec("16500.00"), dec("2"))));
+
+    let newer = message("delta", 121, json!([["16550.00", "1"]]), json!([]));
+    assert_eq!(
+        synced.handle_event(&event(newer)).await.unwrap(),
+        Some(BookUpdate::Delta)
+    );
+    assert_eq!(synced.book().best_bid(), Some((dec("16550.00"), dec("1"))));
+
+    let next = message("delta", 122, json!([]), json!([]));
+    assert_eq!(
+        synced.handle_event(&event(next)).await.unwrap(),
+        Some(BookUpdate::Delta)
+    );
+}
+
+#[tokio::test]
+async fn reconnect_invalidates_until_the_next_snapshot() {
+    let (handle, _ops) = stream_handle().await;
+    let mut synced = SyncedOrderBook::new(50, "BTCUSDT", handle);
+    synced.handle_event(&event(snapshot())).await.unwrap();
+    assert!(synced.book().is_synced());
+
+    assert_eq!(
+        synced
+            .handle_event(&StreamEvent::Reconnected)
+            .await
+            .unwrap(),
+        None
+    );
+    assert!(!synced.book().is_synced());
+    assert_eq!(synced.book().best_bid(), None);
+
+    let delta = message("delta", 101, json!([]), json!([]));
+    assert_eq!(
+        synced.handle_event(&event(delta)).await.unwrap(),
+        Some(BookUpdate::AwaitingSnapshot)
+    );
+
+    synced.handle_event(&event(snapshot())).await.unwrap();
+    assert!(synced.book().is_synced());
+    assert_eq!(
+        synced.book().best_bid(),
+        Some((dec("16493.50"), dec("0.006")))
+    );
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use std::{collections::HashMap, sync::Arc};
+
+use bybit_rs::{
+    bybit::{
+        asset::{Asset, AssetHTTP},
+        http_manager::HttpManager,
+        pagination::{split_range, SEVEN_DAYS},
+        position::{Position, PositionHTTP},
+        trade::{Trade, TradeHTTP},
+    },
+    errors::app_error::AppError,
+};
+use chrono::{TimeZone, Utc};
+use futures::{StreamExt, TryStreamExt};
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use serde_json::{json, Value};
+
+use common::{envelope, manager};
+
+async fn page(server: &mut ServerGuard, path: &str, cursor: Option<&str>, result: Value) -> Mock {
+    let query = match cursor {
+        Some(cursor) => Matcher::UrlEncoded("cursor".into(), cursor.into()),
+        None => Matcher::Any,
+    };
+    server
+        .mock("GET", path)
+        .match_query(query)
+        .with_body(envelope(result))
+        .create_async()
+        .await
+}
+
+#[tokio::test]
+async fn follows_the_cursor_until_it_runs_out() {
+    let mut server = Server::new_async().await;
+    let path = "/v5/order/history";
+    // Cursor specific pages first, so the first request falls through to the catch-all.
+    let third = page(
+        &mut server,
+        path,
+        Some("c2"),
+        json!({"list": [{"orderId": "5"}], "nextPageCursor": ""}),
+    )
+    .await;
+    let second = page(
+        &mut server,
+        path,
+        Some("c1"),
+        json!({"list": [{"orderId": "3"}, {"orderId": "4"}], "nextPageCursor": "c2"}),
+    )
+    .await;
+    let first = page(
+        &mut server,
+        path,
+        None,
+        json!({"category": "linear", "list": [{"orderId": "1"}, {"orderId": "2"}], "nextPageCursor": "c1"}),
+    )
+    .await;
+
+    let trade = TradeHTTP::new(manager(&server));
+    let orders: Vec<Value> = trade
+        .get_order_history_stream(HashMap::new())
+        .try_collect()
+        .await
+        .unwrap();
+    let ids: Vec<&str> = orders
+        .iter()
+        .map(|order| order["orderId"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, ["1", "2", "3", "4", "5"]);
+    for mock in [first, second, third] {
+        mock.assert_async().await;
+    }
+}
+
+#[tokio::test]
+async fn pages_are_fetched_lazily() {
+    let mut server = Server::new_async().await;
+    let path = "/v5/execution/list";
+    let second = page(
+        &mut server,
+        path,
+        Some("c1"),
+        json!({"list": [], "nextPageCursor": ""}),
+    )
+    .await
+    .expect(0);
+    let first = page(
+        &mut server,
+        path,
+        None,
+        json!({"list": [{"execId": "a"}, {"execId": "b"}], "nextPageCursor": "c1"}),
+    )
+    .await;
+
+    let position = PositionHTTP::new(manager(&server));
+    let firsts: Vec<Value> = position
+        .get_executions_stream(HashMap::new())
+        .take(2)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(firsts.len(), 2);
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+#[derive(Debug, serde_derive::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Deposit {
+    coin: String,
+    amount: String,
+}
+
+#[tokio::test]
+async fn record_rows_deserialize_and_errors_end_the_stream() {
+    let mut server = Server::new_async().await;
+    let path = "/v5/asset/deposit/query-record";
+    server
+        .mock("GET", path)
+        .match_query(Matcher::UrlEncoded("cursor".into(), "c1".into()))
+        .with_body(json!({"retCode": 10006, "retMsg": "Too many visits!", "result": {}, "retExtInfo": {}, "time": 0}).to_string())
+        .create_async()
+        .await;
+    page(
+        &mut server,
+        path,
+        None,
+        json!({"rows": [{"coin": "USDT", "amount": "100", "status": 3}], "nextPageCursor": "c1"}),
+    )
+    .await;
+
+    let manager = HttpManager::builder("key".to_owned(), "secret".to_owned())
+        .base_url(&server.url())
+        .build()
+        .unwrap()
+        .with_max_retries(0);
+    let asset = AssetHTTP::new(Arc::new(manager));
+    let mut deposits = asset.get_deposit_records_stream::<Deposit>(HashMap::new());
+    let deposit = deposits.next().await.unwrap().unwrap();
+    assert_eq!(
+        (deposit.coin.as_str(), deposit.amount.as_str()),
+        ("USDT", "100")
+    );
+    assert!(matches!(
+        deposits.next().await,
+        Some(Err(AppError::RateLimited { .. }))
+    ));
+    assert!(deposits.next().await.is_none());
+}
+
+#[test]
+fn ranges_split_into_inclusive_windows_newest_first() {
+    let start = Utc.timestamp_millis_opt(0).unwrap();
+    let day = 24 * 60 * 60 * 1000;
+    let windows = split_range(start, start + chrono::Duration::days(15), SEVEN_DAYS).unwrap();
+    assert_eq!(
+        windows,
+        [(8 * day + 1, 15 * day), (day + 1, 8 * day), (0, day)]
+    );
+    assert!(split_range(start + chrono::Duration::days(1), start, SEVEN_DAYS).is_err());
+}
+
+#[tokio::test]
+async fn range_queries_each_window_and_skips_edge_duplicates() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/v5/execution/list")
+        .match_query(Matcher::Any)
+        .with_body_from_request(|request| {
+            let query: HashMap<String, String> =
+                url::form_urlencoded::parse(request.path_and_query().split('?').nth(1).unwrap().as_bytes())
+                    .into_owned()
+                    .collect();
+            let start: i64 = query["startTime"].parse().unwrap();
+            let end: i64 = query["endTime"].parse().unwrap();
+            assert!(end - start < 7 * 24 * 60 * 60 * 1000);
+            json!({
+                "retCode": 0, "retMsg": "OK", "retExtInfo": {}, "time": 0,
+                "result": {"list": [{"execId": "edge"}, {"execId": start.to_string()}], "nextPageCursor": ""},
+            })
+            .to_string()
+            .into()
+        })
+        .expect(3)
+        .create_async()
+        .await;
+
+    let start = Utc.timestamp_millis_opt(1_700_000_000_000).unwrap();
+    let position = PositionHTTP::new(manager(&server));
+    let executions: Vec<Value> = position
+        .get_executions_range(HashMap::new(), start, start + chrono::Duration::days(20))
+        .try_collect()
+        .await
+        .unwrap();
+    let ids: Vec<&str> = executions
+        .iter()
+        .map(|e| e["execId"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids.len(), 4);
+    assert_eq!(ids[0], "edge");
+    assert_eq!(ids[3], "1700000000000");
+    mock.assert_async().await;
+}
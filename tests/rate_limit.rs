@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+use bybit_rs::{
+    bybit::rate_limit::{EndpointGroup, RateLimitMode, RateLimiter},
+    errors::app_error::AppError,
+};
+use reqwest::header::{HeaderMap, HeaderValue};
+
+const CREATE: &str = "/v5/order/create";
+
+#[tokio::test]
+async fn reject_mode_fails_once_the_window_is_used() {
+    let limiter = RateLimiter::new(RateLimitMode::Reject).with_limit(
+        EndpointGroup::Order,
+        2,
+        Duration::from_secs(1),
+    );
+
+    limiter.acquire(CREATE, true).await.unwrap();
+    limiter.acquire(CREATE, true).await.unwrap();
+    match limiter.acquire(CREATE, true).await {
+        Err(AppError::Throttled { path, retry_after }) => {
+            assert_eq!(path, CREATE);
+            assert!(retry_after <= Duration::from_secs(1));
+        }
+        other => panic!("expected a throttled call, got {:?}", other),
+    }
+
+    // each private endpoint has its own window
+    limiter.acquire("/v5/order/amend", true).await.unwrap();
+}
+
+#[tokio::test]
+async fn queue_mode_waits_for_the_reset() {
+    let limiter = RateLimiter::new(RateLimitMode::Queue).with_limit(
+        EndpointGroup::Public,
+        1,
+        Duration::from_millis(200),
+    );
+
+    let started = Instant::now();
+    limiter.acquire("/v5/market/tickers", false).await.unwrap();
+    limiter.acquire("/v5/market/kline", false).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn headers_override_the_local_count() {
+    let limiter = RateLimiter::new(RateLimitMode::Reject);
+    limiter.acquire(CREATE, true).await.unwrap();
+
+    let reset = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis()
+        + 500;
+    let mut headers = HeaderMap::new();
+    headers.insert("X-Bapi-Limit", HeaderValue::from_static("10"));
+    headers.insert("X-Bapi-Limit-Status", HeaderValue::from_static("0"));
+    headers.insert(
+        "X-Bapi-Limit-Reset-Timestamp",
+        HeaderValue::from_str(&reset.to_string()).unwrap(),
+    );
+    limiter.update(CREATE, true, &headers);
+
+    assert!(matches!(
+        limiter.acquire(CREATE, true).await,
+        Err(AppError::Throttled { .. })
+    ));
+}
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use bybit_rs::{
+    bybit::{
+        http_manager::{HttpManager, Manager},
+        signer::{HmacSigner, RsaSigner, Signer},
+        websocket_stream,
+    },
+    errors::app_error::AppError,
+};
+use mockito::Server;
+use openssl::{hash::MessageDigest, pkey::PKey, rsa::Rsa, sign::Verifier};
+use reqwest::Method;
+use serde_json::json;
+
+fn rsa_key() -> (RsaSigner, PKey<openssl::pkey::Public>) {
+    let rsa = Rsa::generate(2048).unwrap();
+    let pem = rsa.private_key_to_pem().unwrap();
+    let public = PKey::public_key_from_pem(&rsa.public_key_to_pem().unwrap()).unwrap();
+    (RsaSigner::from_pem(&pem).unwrap(), public)
+}
+
+fn verify(public: &PKey<openssl::pkey::Public>, payload: &str, signature: &str) -> bool {
+    let signature = openssl::base64::decode_block(signature).unwrap();
+    let mut verifier = Verifier::new(MessageDigest::sha256(), public).unwrap();
+    verifier.update(payload.as_bytes()).unwrap();
+    verifier.verify(&signature).unwrap()
+}
+
+#[test]
+fn hmac_signs_hex_sha256() {
+    let signer = HmacSigner::new("key");
+    assert_eq!(
+        signer
+            .sign("The quick brown fox jumps over the lazy dog")
+            .unwrap(),
+        "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+    );
+    assert_eq!(signer.sign_type(), Some("2"));
+    assert!(matches!(
+        HmacSigner::new("").sign("payload"),
+        Err(AppError::MissingCredentials)
+    ));
+}
+
+#[test]
+fn rsa_signs_base64_sha256() {
+    let (signer, public) = rsa_key();
+    let signature = signer.sign("GET/realtime1700000000000").unwrap();
+    assert!(verify(&public, "GET/realtime1700000000000", &signature));
+    assert_eq!(signer.sign_type(), None);
+
+    let message = websocket_stream::signed_auth_message("key", &signer, 1700000000000).unwrap();
+    let message: serde_json::Value = serde_json::from_str(&message).unwrap();
+    let signature = message["args"][2].as_str().unwrap();
+    assert!(verify(&public, "GET/realtime1700000000000", signature));
+
+    assert!(RsaSigner::from_pem(b"not a key").is_err());
+}
+
+#[tokio::test]
+async fn manager_signs_requests_with_the_injected_signer() {
+    let (signer, public) = rsa_key();
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v5/position/set-leverage")
+        .with_body_from_request(move |request| {
+            let header = |name: &str| {
+                request
+                    .header(name)
+                    .first()
+                    .map(|value| value.to_str().unwrap().to_owned())
+            };
+            let payload = format!(
+                "{}{}{}{}",
+                header("X-BAPI-TIMESTAMP").unwrap(),
+                header("X-BAPI-API-KEY").unwrap(),
+                header("X-BAPI-RECV-WINDOW").unwrap(),
+                String::from_utf8(request.body().unwrap().clone()).unwrap(),
+            );
+            let valid = verify(&public, &payload, &header("X-BAPI-SIGN").unwrap())
+                && header("X-BAPI-SIGN-TYPE").is_none();
+            json!({
+                "retCode": if valid { 0 } else { 10004 },
+                "retMsg": "",
+                "result": {},
+                "retExtInfo": {},
+                "time": 0,
+            })
+            .to_string()
+            .into()
+        })
+        .create_async()
+        .await;
+
+    let manager = HttpManager::builder("key".to_owned(), String::new())
+        .base_url(&server.url())
+        .signer(Arc::new(signer))
+        .build()
+        .unwrap();
+    manager
+        .submit_post_request(
+            Method::POST,
+            "/v5/position/set-leverage",
+            true,
+            json!({"category": "linear", "symbol": "BTCUSDT", "buyLeverage": "5"}),
+        )
+        .await
+        .unwrap();
+    mock.assert_async().await;
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use bybit_rs::{bybit::time_sync::TimeSync, helpers::utils};
+
+#[test]
+fn offset_is_measured_from_the_round_trip_midpoint() {
+    let time_sync = TimeSync::new();
+    assert!(!time_sync.is_synced());
+    assert_eq!(time_sync.offset(), 0);
+
+    // sent at 1_000, answered at 1_200: the server stamped 1_100 local time as 3_100
+    let offset = time_sync.record(1_000, 1_200, 3_100);
+    assert_eq!(offset, 2_000);
+    assert_eq!(time_sync.latency(), Duration::from_millis(200));
+    assert!(time_sync.is_synced());
+
+    // a server clock behind the local one gives a negative offset
+    assert_eq!(time_sync.record(1_000, 1_000, 500), -500);
+}
+
+#[test]
+fn timestamp_applies_the_offset() {
+    let time_sync = TimeSync::new();
+    let local = utils::generate_timestamp().unwrap();
+    time_sync.record(local, local, local + 5_000);
+
+    let corrected = time_sync.timestamp().unwrap();
+    assert!(corrected >= local + 5_000);
+    assert!(corrected < local + 6_000);
+}
@@ -0,0 +1,259 @@
+mod common;
+
+use std::{collections::HashMap, time::Duration};
+
+use bybit_rs::{
+    bybit::{
+        trade_ws::{TradeOp, TradeWs},
+        websocket_stream::Heartbeat,
+    },
+    errors::app_error::AppError,
+};
+use futures::StreamExt;
+use mockito::{Matcher, Server};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+use common::{accept, manager, push, read_op, ServerSocket};
+
+async fn bind() -> (TcpListener, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    (listener, url)
+}
+
+async fn accept_authenticated(listener: &TcpListener) -> ServerSocket {
+    let mut socket = accept(listener).await;
+    let auth = read_op(&mut socket).await;
+    assert_eq!(auth["op"], "auth");
+    push(
+        &mut socket,
+        json!({"success": true, "ret_msg": "", "op": "auth"}),
+    )
+    .await;
+    socket
+}
+
+fn reply(request: &Value, ret_code: i64, data: Value) -> Value {
+    json!({
+        "reqId": request["reqId"],
+        "retCode": ret_code,
+        "retMsg": if ret_code == 0 { "OK" } else { "ab not enough for new order" },
+        "op": request["op"],
+        "data": data,
+        "retExtInfo": {},
+        "header": {},
+        "connId": "c1",
+    })
+}
+
+fn order(symbol: &str) -> HashMap<String, String> {
+    let mut query = HashMap::new();
+    query.insert("category".to_owned(), "linear".to_owned());
+    query.insert("symbol".to_owned(), symbol.to_owned());
+    query.insert("side".to_owned(), "Buy".to_owned());
+    query.insert("orderType".to_owned(), "Market".to_owned());
+    query.insert("qty".to_owned(), "0.01".to_owned());
+    query
+}
+
+#[tokio::test]
+async fn replies_are_matched_by_req_id_in_any_order() {
+    let (listener, url) = bind().await;
+    let server = tokio::spawn(async move {
+        let mut socket = accept_authenticated(&listener).await;
+        let first = read_op(&mut socket).await;
+        let second = read_op(&mut socket).await;
+        push(
+            &mut socket,
+            reply(&second, 0, json!({"orderId": second["args"][0]["symbol"]})),
+        )
+        .await;
+        push(
+            &mut socket,
+            reply(&first, 0, json!({"orderId": first["args"][0]["symbol"]})),
+        )
+        .await;
+        socket.next().await;
+        (first, second)
+    });
+
+    let ws = TradeWs::from_url("key".to_owned(), "secret".to_owned(), url);
+    ws.connect().await.unwrap();
+    let (btc, eth) = tokio::join!(
+        ws.place_order(order("BTCUSDT")),
+        ws.place_order(order("ETHUSDT"))
+    );
+    assert_eq!(btc.unwrap()["orderId"], "BTCUSDT");
+    assert_eq!(eth.unwrap()["orderId"], "ETHUSDT");
+
+    ws.close();
+    let (first, second) = server.await.unwrap();
+    assert_ne!(first["reqId"], second["reqId"]);
+    assert_eq!(first["op"], "order.create");
+    assert!(first["header"]["X-BAPI-TIMESTAMP"].is_string());
+}
+
+#[tokio::test]
+async fn timed_out_requests_ignore_late_replies() {
+    let (listener, url) = bind().await;
+    tokio::spawn(async move {
+        let mut socket = accept_authenticated(&listener).await;
+        let slow = read_op(&mut socket).await;
+        let next = read_op(&mut socket).await;
+        push(&mut socket, reply(&slow, 0, json!({"orderId": "late"}))).await;
+        push(&mut socket, reply(&next, 0, json!({"orderId": "next"}))).await;
+        socket.next().await;
+    });
+
+    let ws = TradeWs::from_url("key".to_owned(), "secret".to_owned(), url);
+    ws.connect().await.unwrap();
+    let slow = ws
+        .request(TradeOp::Create, order("BTCUSDT"), Duration::from_millis(50))
+        .await;
+    assert!(matches!(slow, Err(AppError::Timeout(_))));
+
+    let next = ws.place_order(order("BTCUSDT")).await.unwrap();
+    assert_eq!(next["orderId"], "next");
+    ws.close();
+}
+
+#[tokio::test]
+async fn non_zero_ret_code_is_returned_as_an_error() {
+    let (listener, url) = bind().await;
+    tokio::spawn(async move {
+        let mut socket = accept_authenticated(&listener).await;
+        let rejected = read_op(&mut socket).await;
+        push(&mut socket, reply(&rejected, 110007, json!({}))).await;
+        let raw = read_op(&mut socket).await;
+        push(&mut socket, reply(&raw, 110007, json!({}))).await;
+        socket.next().await;
+    });
+
+    let ws = TradeWs::from_url("key".to_owned(), "secret".to_owned(), url);
+    ws.connect().await.unwrap();
+    let rejected = ws.place_order(order("BTCUSDT")).await;
+    assert!(matches!(
+        rejected,
+        Err(AppError::InsufficientBalance {
+            ret_code: 110007,
+            ..
+        })
+    ));
+
+    let raw = ws
+        .request(TradeOp::Create, order("BTCUSDT"), Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(raw["retCode"], 110007);
+    ws.close();
+}
+
+#[tokio::test]
+async fn pending_requests_fail_when_the_socket_drops() {
+    let (listener, url) = bind().await;
+    tokio::spawn(async move {
+        let mut socket = accept_authenticated(&listener).await;
+        read_op(&mut socket).await;
+        drop(socket);
+    });
+
+    let ws = TradeWs::from_url("key".to_owned(), "secret".to_owned(), url);
+    ws.connect().await.unwrap();
+    let dropped = ws.place_order(order("BTCUSDT")).await;
+    assert!(matches!(dropped, Err(AppError::ConnectionClosed(_))));
+}
+
+#[tokio::test]
+async fn pings_keep_the_socket_alive() {
+    let (listener, url) = bind().await;
+    let server = tokio::spawn(async move {
+        let mut socket = accept_authenticated(&listener).await;
+        let mut pings = 0;
+        let create = loop {
+            let op = read_op(&mut socket).await;
+            if op["op"] != "ping" {
+                break op;
+            }
+            push(
+                &mut socket,
+                json!({"op": "pong", "args": ["1"], "connId": "c1", "req_id": op["req_id"]}),
+            )
+            .await;
+            pings += 1;
+        };
+        push(&mut socket, reply(&create, 0, json!({"orderId": "alive"}))).await;
+        pings
+    });
+
+    let ws =
+        TradeWs::from_url("key".to_owned(), "secret".to_owned(), url).with_heartbeat(Heartbeat {
+            interval: Duration::from_millis(30),
+            stale_after: Duration::from_millis(100),
+        });
+    ws.connect().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(ws.is_connected());
+
+    let created = ws.place_order(order("BTCUSDT")).await.unwrap();
+    assert_eq!(created["orderId"], "alive");
+    ws.close();
+    assert!(server.await.unwrap() >= 3);
+}
+
+#[tokio::test]
+async fn stale_socket_is_dropped() {
+    let (listener, url) = bind().await;
+    tokio::spawn(async move {
+        // reads requests and pings but never answers
+        let mut socket = accept_authenticated(&listener).await;
+        while socket.next().await.is_some() {}
+    });
+
+    let ws =
+        TradeWs::from_url("key".to_owned(), "secret".to_owned(), url).with_heartbeat(Heartbeat {
+            interval: Duration::from_millis(20),
+            stale_after: Duration::from_millis(100),
+        });
+    ws.connect().await.unwrap();
+    let pending = ws.place_order(order("BTCUSDT")).await;
+    assert!(matches!(pending, Err(AppError::ConnectionClosed(_))));
+    assert!(!ws.is_connected());
+}
+
+#[tokio::test]
+async fn disconnected_requests_go_through_rest_when_a_fallback_is_set() {
+    let mut server = Server::new_async().await;
+    let create = server
+        .mock("POST", "/v5/order/create")
+        .match_body(Matcher::PartialJson(json!({"symbol": "BTCUSDT"})))
+        .with_body(
+            json!({
+                "retCode": 0,
+                "retMsg": "OK",
+                "result": {"orderId": "rest", "orderLinkId": ""},
+                "retExtInfo": {},
+                "time": 0,
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let manager = manager(&server);
+
+    let offline = TradeWs::from_url(
+        "key".to_owned(),
+        "secret".to_owned(),
+        "ws://unused".to_owned(),
+    );
+    assert!(matches!(
+        offline.place_order(order("BTCUSDT")).await,
+        Err(AppError::ConnectionClosed(_))
+    ));
+
+    let ws = offline.with_fallback(manager);
+    assert!(!ws.is_connected());
+    let result = ws.place_order(order("BTCUSDT")).await.unwrap();
+    assert_eq!(result["orderId"], "rest");
+    create.assert_async().await;
+}
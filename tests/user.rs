@@ -0,0 +1,173 @@
+mod common;
+
+use std::collections::HashMap;
+
+use bybit_rs::{
+    bybit::user::{User, UserHTTP},
+    models::user::{
+        ApiPermissions, CreateSubApiKeyRequest, CreateSubUidRequest, DeleteSubUidRequest,
+        FreezeSubUidRequest,
+    },
+};
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+use common::{manager, reply};
+
+#[tokio::test]
+async fn sub_account_writes_are_posted_as_json() {
+    let mut server = Server::new_async().await;
+    let create = reply(
+        &mut server,
+        "POST",
+        "/v5/user/create-sub-member",
+        Matcher::Json(json!({"username": "trader01", "memberType": 1, "isUta": true})),
+        json!({"uid": "53888000", "username": "trader01", "memberType": 1, "status": 1, "remark": ""}),
+    )
+    .await;
+    let api_key = reply(
+        &mut server,
+        "POST",
+        "/v5/user/create-sub-api",
+        Matcher::Json(json!({
+            "subuid": 53888000,
+            "readOnly": 0,
+            "permissions": {"ContractTrade": ["Order", "Position"], "Wallet": ["AccountTransfer"]},
+        })),
+        json!({
+            "id": "16651283", "note": "", "apiKey": "XXXX", "readOnly": 0, "secret": "YYYY",
+            "permissions": {"ContractTrade": ["Order", "Position"], "Wallet": ["AccountTransfer"]},
+        }),
+    )
+    .await;
+    let freeze = reply(
+        &mut server,
+        "POST",
+        "/v5/user/frozen-sub-member",
+        Matcher::Json(json!({"subuid": 53888000, "frozen": 1})),
+        json!({}),
+    )
+    .await;
+    let delete = reply(
+        &mut server,
+        "POST",
+        "/v5/user/del-submember",
+        Matcher::Json(json!({"subMemberId": "53888000"})),
+        json!({}),
+    )
+    .await;
+
+    let user = UserHTTP::new(manager(&server));
+    let member = user
+        .create_sub_uid_typed(&CreateSubUidRequest::new("trader01", 1).is_uta(true))
+        .await
+        .unwrap();
+    assert_eq!(member.uid, 53888000);
+
+    let permissions = ApiPermissions {
+        contract_trade: Some(vec!["Order".to_owned(), "Position".to_owned()]),
+        wallet: Some(vec!["AccountTransfer".to_owned()]),
+        ..ApiPermissions::default()
+    };
+    let key = user
+        .create_sub_api_key_typed(&CreateSubApiKeyRequest::new(member.uid, false, permissions))
+        .await
+        .unwrap();
+    assert_eq!(key.id, 16651283);
+    assert_eq!(key.secret, "YYYY");
+    assert!(!key.read_only);
+    assert_eq!(key.permissions.wallet.unwrap(), ["AccountTransfer"]);
+
+    user.freeze_sub_uid(&FreezeSubUidRequest::new(member.uid, true))
+        .await
+        .unwrap();
+    user.delete_sub_uid(&DeleteSubUidRequest::new(member.uid))
+        .await
+        .unwrap();
+
+    for mock in [create, api_key, freeze, delete] {
+        mock.assert_async().await;
+    }
+}
+
+#[tokio::test]
+async fn lists_sub_api_keys_and_wallet_types() {
+    let mut server = Server::new_async().await;
+    let keys = server
+        .mock("GET", "/v5/user/sub-apikeys")
+        .match_query(Matcher::UrlEncoded(
+            "subMemberId".into(),
+            "100400345".into(),
+        ))
+        .with_body(
+            json!({"retCode": 0, "retMsg": "OK", "retExtInfo": {}, "time": 0, "result": {
+                "result": [{
+                    "id": "24828209", "ips": ["*"], "apiKey": "XXXX", "note": "test", "status": 1,
+                    "expiredAt": "2023-12-01T02:36:06Z", "createdAt": "2023-08-25T02:36:06Z",
+                    "type": 1, "permissions": {"Spot": ["SpotTrade"]}, "secret": "********",
+                    "readOnly": true, "deadlineDay": 98, "flag": "hmac",
+                }],
+                "nextPageCursor": "",
+            }})
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let wallets = reply(
+        &mut server,
+        "GET",
+        "/v5/user/get-member-type",
+        Matcher::Any,
+        json!({"accounts": [{"uid": "533285", "accountType": ["SPOT", "UNIFIED"]}]}),
+    )
+    .await;
+
+    let user = UserHTTP::new(manager(&server));
+    let mut query = HashMap::new();
+    query.insert("subMemberId".to_owned(), "100400345".to_owned());
+    let page = user.get_sub_api_keys_typed(query).await.unwrap();
+    assert_eq!(page.list.len(), 1);
+    assert!(page.list[0].read_only);
+    assert_eq!(
+        page.list[0].permissions.spot.as_ref().unwrap(),
+        &["SpotTrade"]
+    );
+
+    let types = user
+        .get_uid_wallet_type_typed(HashMap::new())
+        .await
+        .unwrap();
+    assert_eq!(types.accounts[0].uid, 533285);
+    assert_eq!(types.accounts[0].account_type, ["SPOT", "UNIFIED"]);
+
+    keys.assert_async().await;
+    wallets.assert_async().await;
+}
+
+#[tokio::test]
+async fn reads_affiliate_customer_info() {
+    let mut server = Server::new_async().await;
+    let _mock = reply(
+        &mut server,
+        "GET",
+        "/v5/user/aff-customer-info",
+        Matcher::Any,
+        json!({
+            "uid": "1513500", "vipLevel": "Lv0", "takerVol30Day": "10.5", "makerVol30Day": "0",
+            "tradeVol30Day": "10.5", "depositAmount30Day": "", "takerVol365Day": "20",
+            "makerVol365Day": "0", "tradeVol365Day": "20", "depositAmount365Day": "100",
+            "totalWalletBalance": "1", "depositUpdateTime": "2023-09-19 02:32:00",
+            "volUpdateTime": "2023-09-19 02:32:00", "KycLevel": 1,
+        }),
+    )
+    .await;
+
+    let user = UserHTTP::new(manager(&server));
+    let mut query = HashMap::new();
+    query.insert("uid".to_owned(), "1513500".to_owned());
+    let info = user.get_affiliate_customer_info_typed(query).await.unwrap();
+    assert_eq!(info.uid, 1513500);
+    assert_eq!(info.taker_vol30_day.to_string(), "10.5");
+    assert!(info.deposit_amount30_day.is_zero());
+    assert_eq!(info.kyc_level, 1);
+}
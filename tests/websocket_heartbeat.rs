@@ -0,0 +1,84 @@
+mod common;
+
+use std::time::Duration;
+
+use bybit_rs::bybit::websocket_stream::{Heartbeat, PublicStream, StreamEvent};
+use futures::StreamExt;
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use common::{accept, fast_policy, next_event, push, read_op};
+
+#[tokio::test]
+async fn ping_replies_update_latency() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        let ping = read_op(&mut socket).await;
+        assert_eq!(ping["op"], "ping");
+        push(
+            &mut socket,
+            json!({"success": true, "ret_msg": "pong", "op": "ping", "req_id": ping["req_id"]}),
+        )
+        .await;
+        push(&mut socket, json!({"topic": "tickers.BTCUSDT", "data": {}})).await;
+        socket.next().await;
+    });
+
+    let stream = PublicStream::from_url(url).with_heartbeat(Heartbeat {
+        interval: Duration::from_millis(20),
+        stale_after: Duration::from_secs(5),
+    });
+    let (handle, mut events) = stream.connect().await.unwrap();
+
+    // the pong is consumed by the heartbeat, the first event is the ticker
+    assert!(matches!(
+        next_event(&mut events).await.unwrap(),
+        StreamEvent::Message(_)
+    ));
+    let health = handle.health();
+    assert!(health.is_connected());
+    assert!(health.latency().is_some());
+    assert!(health.last_message_age().is_some());
+    handle.close();
+}
+
+#[tokio::test]
+async fn silent_socket_is_flagged_stale_and_reconnected() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        // never answers pings
+        let mut silent = accept(&listener).await;
+        let mut second = accept(&listener).await;
+        read_op(&mut second).await;
+        push(
+            &mut second,
+            json!({"success": true, "ret_msg": "pong", "op": "ping"}),
+        )
+        .await;
+        second.next().await;
+        silent.next().await;
+    });
+
+    let stream = PublicStream::from_url(url)
+        .with_reconnect_policy(fast_policy())
+        .with_heartbeat(Heartbeat {
+            interval: Duration::from_millis(20),
+            stale_after: Duration::from_millis(100),
+        });
+    let (handle, mut events) = stream.connect().await.unwrap();
+
+    assert!(matches!(
+        next_event(&mut events).await.unwrap(),
+        StreamEvent::Reconnected
+    ));
+    let snapshot = handle.health().snapshot();
+    assert!(snapshot.connected);
+    assert!(!snapshot.stale);
+    assert_eq!(snapshot.reconnects, 1);
+    handle.close();
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use bybit_rs::bybit::websocket_stream::{
+    PrivateEvent, PrivateStream, PrivateTopic, PublicStream, ReconnectPolicy, StreamEvent,
+};
+use futures::StreamExt;
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use common::{accept, fast_policy, next_event, push, read_op};
+
+#[tokio::test]
+async fn public_stream_resubscribes_after_drop() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let mut first = accept(&listener).await;
+        let subscribe = read_op(&mut first).await;
+        assert_eq!(subscribe["op"], "subscribe");
+        let unsubscribe = read_op(&mut first).await;
+        assert_eq!(unsubscribe["args"], json!(["tickers.BTCUSDT"]));
+        push(&mut first, json!({"topic": "orderbook.1.BTCUSDT", "data": {"u": 1}})).await;
+        // drop without a close frame
+        drop(first);
+
+        let mut second = accept(&listener).await;
+        let resubscribe = read_op(&mut second).await;
+        push(&mut second, json!({"topic": "orderbook.1.BTCUSDT", "data": {"u": 2}})).await;
+        second.next().await;
+        resubscribe
+    });
+
+    let stream = PublicStream::from_url(url).with_reconnect_policy(fast_policy());
+    let (handle, mut events) = stream
+        .subscribe(vec![
+            "orderbook.1.BTCUSDT".to_owned(),
+            "tickers.BTCUSDT".to_owned(),
+        ])
+        .await
+        .unwrap();
+    handle.unsubscribe(vec!["tickers.BTCUSDT".to_owned()]).unwrap();
+
+    match next_event(&mut events).await.unwrap() {
+        StreamEvent::Message(message) => assert_eq!(message.data["u"], 1),
+        other => panic!("unexpected event {:?}", other),
+    }
+    assert!(matches!(
+        next_event(&mut events).await.unwrap(),
+        StreamEvent::Reconnected
+    ));
+    match next_event(&mut events).await.unwrap() {
+        StreamEvent::Message(message) => assert_eq!(message.data["u"], 2),
+        other => panic!("unexpected event {:?}", other),
+    }
+
+    handle.close();
+    let resubscribe = server.await.unwrap();
+    assert_eq!(resubscribe["op"], "subscribe");
+    assert_eq!(resubscribe["args"], json!(["orderbook.1.BTCUSDT"]));
+}
+
+#[tokio::test]
+async fn private_stream_reauthenticates_after_drop() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let mut ops = Vec::new();
+        for _ in 0..2 {
+            let mut socket = accept(&listener).await;
+            let auth = read_op(&mut socket).await;
+            push(&mut socket, json!({"success": true, "ret_msg": "", "op": "auth"})).await;
+            let subscribe = read_op(&mut socket).await;
+            push(
+                &mut socket,
+                json!({"id": "1", "topic": "order", "creationTime": 1, "data": []}),
+            )
+            .await;
+            ops.push((auth, subscribe));
+        }
+        ops
+    });
+
+    let stream = PrivateStream::from_url("key".to_owned(), "secret".to_owned(), url)
+        .with_reconnect_policy(fast_policy());
+    let (_handle, mut events) = stream
+        .subscribe(vec![PrivateTopic::Order, PrivateTopic::Wallet])
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        next_event(&mut events).await.unwrap(),
+        PrivateEvent::Order(_)
+    ));
+    assert!(matches!(
+        next_event(&mut events).await.unwrap(),
+        PrivateEvent::Reconnected
+    ));
+    assert!(matches!(
+        next_event(&mut events).await.unwrap(),
+        PrivateEvent::Order(_)
+    ));
+
+    let ops = server.await.unwrap();
+    for (auth, subscribe) in ops {
+        assert_eq!(auth["op"], "auth");
+        assert_eq!(auth["args"][0], "key");
+        assert_eq!(subscribe["args"], json!(["order", "wallet"]));
+    }
+}
+
+#[tokio::test]
+async fn disabled_policy_ends_stream_with_error() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        let socket = accept(&listener).await;
+        drop(socket);
+    });
+
+    let stream = PublicStream::from_url(url).with_reconnect_policy(ReconnectPolicy::disabled());
+    let (_handle, mut events) = stream.connect().await.unwrap();
+
+    assert!(next_event(&mut events).await.is_err());
+    assert!(events.next().await.is_none());
+}
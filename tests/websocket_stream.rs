@@ -0,0 +1,236 @@
+mod common;
+
+use bybit_rs::{
+    bybit::websocket_stream::{
+        auth_message, stream_url, topic, PrivateEvent, PrivateStream, PrivateTopic, PublicChannel,
+        PublicStream, StreamEvent,
+    },
+    errors::app_error::AppError,
+};
+use serde_derive::Deserialize;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+use common::{accept, next_event, push, read_op};
+
+#[test]
+fn builds_urls_and_topic_names() {
+    assert_eq!(
+        stream_url("/v5/public/linear", false),
+        "wss://stream.bybit.com/v5/public/linear"
+    );
+    assert_eq!(
+        PublicStream::new(PublicChannel::Spot, true).url(),
+        "wss://stream-testnet.bybit.com/v5/public/spot"
+    );
+    assert_eq!(topic::orderbook(50, "BTCUSDT"), "orderbook.50.BTCUSDT");
+    assert_eq!(topic::public_trade("ETHUSDT"), "publicTrade.ETHUSDT");
+    assert_eq!(topic::tickers("BTCUSDT"), "tickers.BTCUSDT");
+    assert_eq!(topic::kline("5", "BTCUSDT"), "kline.5.BTCUSDT");
+}
+
+#[tokio::test]
+async fn subscribe_is_acked_and_topics_are_delivered() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        let subscribe = read_op(&mut socket).await;
+        push(
+            &mut socket,
+            json!({"success": true, "ret_msg": "subscribe", "conn_id": "c1", "op": "subscribe"}),
+        )
+        .await;
+        push(
+            &mut socket,
+            json!({
+                "topic": "orderbook.1.BTCUSDT",
+                "type": "snapshot",
+                "ts": 1672304484978u64,
+                "cts": 1672304484961u64,
+                "data": {"s": "BTCUSDT", "b": [["16493.50", "0.006"]], "a": [], "u": 18521288},
+            }),
+        )
+        .await;
+        read_op(&mut socket).await;
+        subscribe
+    });
+
+    let stream = PublicStream::from_url(url);
+    let (handle, mut events) = stream
+        .subscribe(vec![topic::orderbook(1, "BTCUSDT")])
+        .await
+        .unwrap();
+
+    match next_event(&mut events).await.unwrap() {
+        StreamEvent::Response(ack) => {
+            assert!(ack.success);
+            assert_eq!(ack.op.as_deref(), Some("subscribe"));
+            assert_eq!(ack.conn_id, "c1");
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+    match next_event(&mut events).await.unwrap() {
+        StreamEvent::Message(message) => {
+            assert_eq!(message.topic, "orderbook.1.BTCUSDT");
+            assert_eq!(message.kind.as_deref(), Some("snapshot"));
+            assert_eq!(message.ts, Some(1672304484978));
+            assert_eq!(message.data["u"], 18521288);
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+
+    handle
+        .unsubscribe(vec![topic::orderbook(1, "BTCUSDT")])
+        .unwrap();
+    let subscribe = server.await.unwrap();
+    assert_eq!(
+        subscribe,
+        json!({"op": "subscribe", "args": ["orderbook.1.BTCUSDT"]})
+    );
+    handle.close();
+}
+
+#[tokio::test]
+async fn large_subscriptions_are_split_into_requests_of_ten() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        let first = read_op(&mut socket).await;
+        let second = read_op(&mut socket).await;
+        (first, second)
+    });
+
+    let topics: Vec<String> = (0..12)
+        .map(|i| topic::tickers(&format!("COIN{}USDT", i)))
+        .collect();
+    let (handle, _events) = PublicStream::from_url(url)
+        .subscribe(topics.clone())
+        .await
+        .unwrap();
+
+    let (first, second) = server.await.unwrap();
+    assert_eq!(first["args"], json!(topics[..10]));
+    assert_eq!(second["args"], json!(topics[10..]));
+    handle.close();
+}
+
+#[test]
+fn auth_message_signs_the_expiry() {
+    let message: Value =
+        serde_json::from_str(&auth_message("key", "secret", 1700000000000).unwrap()).unwrap();
+    assert_eq!(
+        message,
+        json!({
+            "op": "auth",
+            "args": [
+                "key",
+                1700000000000u64,
+                "9baf584ddf7a063dffe910d97ce4eac0cf7064058356de8b8d92f028e5ad936f",
+            ],
+        })
+    );
+}
+
+#[tokio::test]
+async fn private_stream_authenticates_before_subscribing() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        let auth = read_op(&mut socket).await;
+        push(
+            &mut socket,
+            json!({"success": true, "ret_msg": "", "op": "auth", "conn_id": "c1"}),
+        )
+        .await;
+        let subscribe = read_op(&mut socket).await;
+        push(
+            &mut socket,
+            json!({
+                "id": "5923240c6880ab-c59f-420b-9adb-3639adc9dd90",
+                "topic": "order.linear",
+                "creationTime": 1672364262474u64,
+                "data": [
+                    {"symbol": "ETHUSDT", "orderId": "5cf98598", "qty": "0.1"},
+                    {"symbol": "BTCUSDT", "orderId": "1f2c0a4e", "qty": "0.01"},
+                ],
+            }),
+        )
+        .await;
+        read_op(&mut socket).await;
+        (auth, subscribe)
+    });
+
+    let stream = PrivateStream::from_url("key".to_owned(), "secret".to_owned(), url);
+    let (handle, mut events) = stream
+        .subscribe(vec![PrivateTopic::Order, PrivateTopic::Execution])
+        .await
+        .unwrap();
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct OrderUpdate {
+        symbol: String,
+        order_id: String,
+    }
+
+    match next_event(&mut events).await.unwrap() {
+        PrivateEvent::Order(message) => {
+            assert_eq!(message.topic, "order.linear");
+            assert_eq!(message.creation_time, 1672364262474);
+            let orders: Vec<OrderUpdate> = message.data_as().unwrap();
+            assert_eq!(orders.len(), 2);
+            assert_eq!(orders[1].symbol, "BTCUSDT");
+            assert_eq!(orders[1].order_id, "1f2c0a4e");
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+
+    handle
+        .unsubscribe(vec![PrivateTopic::Order.to_string()])
+        .unwrap();
+    let (auth, subscribe) = server.await.unwrap();
+    let expires = auth["args"][1].as_u64().unwrap() as u128;
+    let expected: Value =
+        serde_json::from_str(&auth_message("key", "secret", expires).unwrap()).unwrap();
+    assert_eq!(auth, expected);
+    assert_eq!(
+        subscribe,
+        json!({"op": "subscribe", "args": ["order", "execution"]})
+    );
+    handle.close();
+}
+
+#[tokio::test]
+async fn rejected_keys_fail_the_connect() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        read_op(&mut socket).await;
+        push(
+            &mut socket,
+            json!({"success": false, "ret_msg": "Params Error", "op": "auth"}),
+        )
+        .await;
+    });
+
+    let stream = PrivateStream::from_url("key".to_owned(), "bad".to_owned(), url);
+    match stream.connect().await {
+        Err(AppError::WebSocketAuthFailed(message)) => assert_eq!(message, "Params Error"),
+        Err(other) => panic!("unexpected error {:?}", other),
+        Ok(_) => panic!("connected with rejected keys"),
+    }
+
+    let keyless = PrivateStream::from_url(String::new(), String::new(), "ws://unused".to_owned());
+    assert!(matches!(
+        keyless.connect().await,
+        Err(AppError::MissingCredentials)
+    ));
+}